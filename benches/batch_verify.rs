@@ -0,0 +1,39 @@
+//! Compares `batch_verify` against checking each opening with `verify`.
+
+use bls12_381_plus::{G2Affine, G2Projective};
+use commit_groth09::{CommitmentKey, Values};
+use criterion::{criterion_group, criterion_main, Criterion};
+use group::Group;
+use rand::thread_rng;
+
+const N: usize = 16;
+const OPENINGS: usize = 64;
+
+fn batch_verify(c: &mut Criterion) {
+    let ck = CommitmentKey::<N>::generate();
+    let openings: Vec<_> = (0..OPENINGS)
+        .map(|_| {
+            let value =
+                Values::new([(); N].map(|_| G2Affine::from(G2Projective::random(thread_rng()))));
+            let (c, r) = ck.commit(&value);
+            (c, value, r)
+        })
+        .collect();
+
+    let mut group = c.benchmark_group("verify 64 openings");
+    group.sample_size(10);
+    group.bench_function("separately", |b| {
+        b.iter(|| {
+            for (c, value, r) in &openings {
+                ck.verify(c, value, r).unwrap();
+            }
+        })
+    });
+    group.bench_function("batched", |b| {
+        b.iter(|| ck.batch_verify(&openings).unwrap())
+    });
+    group.finish();
+}
+
+criterion_group!(benches, batch_verify);
+criterion_main!(benches);
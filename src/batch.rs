@@ -0,0 +1,204 @@
+//! Verification of many openings under the same key at once.
+
+use crate::{gt_ct_eq, multi_pairing, BatchError, Commitment, CommitmentKey, Randomness, Values};
+use group::prime::PrimeCurveAffine;
+use group::{Curve, Group};
+use pairing::MultiMillerLoop;
+use rand::{thread_rng, Rng};
+use std::iter::zip;
+
+impl<const N: usize, E: MultiMillerLoop> CommitmentKey<N, E> {
+    /// Checks that every `(commitment, value, randomness)` triple is a valid opening.
+    ///
+    /// With random 128-bit exponents `a_k` and `b_k` for each opening, this checks
+    /// `prod c_k^a_k d_k^b_k` against a single multi-pairing of `2N + 4` terms. Since every
+    /// opening shares the key, the openings are combined on the G2 side, as
+    /// `X_i = prod x_ki^a_k` and `Y_i = prod x_ki^b_k` (and likewise for `r_k` and `s_k`), which
+    /// are paired with `g_i` and `h_i`. An invalid opening passes with probability at most
+    /// `2^-128`. If the check fails, the openings are bisected to find the invalid ones, whose
+    /// indices are returned in the error.
+    pub fn batch_verify(
+        &self,
+        openings: &[(Commitment<E>, Values<N, E>, Randomness<E>)],
+    ) -> Result<(), BatchError> {
+        if openings.is_empty() || self.check_batch(openings) {
+            return Ok(());
+        }
+        Err(BatchError::new(self.failing_indices(openings, 0)))
+    }
+
+    fn check_batch(&self, openings: &[(Commitment<E>, Values<N, E>, Randomness<E>)]) -> bool {
+        let mut rng = thread_rng();
+        let a: Vec<u128> = openings.iter().map(|_| rng.gen()).collect();
+        let b: Vec<u128> = openings.iter().map(|_| rng.gen()).collect();
+
+        let gt: Vec<E::Gt> = (openings.iter().map(|(c, _, _)| c.c))
+            .chain(openings.iter().map(|(c, _, _)| c.d))
+            .collect();
+        let expected = short_msm(&gt, &[a.as_slice(), &b].concat());
+
+        // Column `i` holds the `i`th element of every opening, with `r` and `s` last.
+        let column_sums = |i: usize| {
+            let column: Vec<E::G2> = openings
+                .iter()
+                .map(|(_, value, randomness)| match i.checked_sub(N) {
+                    None => value.values[i],
+                    Some(0) => randomness.r,
+                    Some(_) => randomness.s,
+                })
+                .map(|p| p.to_curve())
+                .collect();
+            [short_msm(&column, &a), short_msm(&column, &b)]
+        };
+        #[cfg(feature = "parallel")]
+        let sums: Vec<E::G2> = {
+            use rayon::prelude::*;
+            (0..N + 2)
+                .into_par_iter()
+                .flat_map_iter(column_sums)
+                .collect()
+        };
+        #[cfg(not(feature = "parallel"))]
+        let sums: Vec<E::G2> = (0..N + 2).flat_map(column_sums).collect();
+
+        let mut combined = vec![E::G2Affine::identity(); sums.len()];
+        E::G2::batch_normalize(&sums, &mut combined);
+        let prepared: Vec<E::G2Prepared> = combined.into_iter().map(E::G2Prepared::from).collect();
+
+        // `prepared` alternates between the sums under `a` and under `b`, so interleave the
+        // matching key elements.
+        let key = (zip(&self.g_arr, &self.h_arr))
+            .chain([(&self.gr, &self.hr), (&self.gs, &self.hs)])
+            .flat_map(|(g, h)| [g, h]);
+        let terms: Vec<_> = zip(key, &prepared).collect();
+
+        gt_ct_eq::<E>(&multi_pairing::<E>(&terms), &expected).into()
+    }
+
+    /// Returns the indices of the invalid openings in a batch that is known to fail, offset by
+    /// `offset`.
+    fn failing_indices(
+        &self,
+        openings: &[(Commitment<E>, Values<N, E>, Randomness<E>)],
+        offset: usize,
+    ) -> Vec<usize> {
+        if openings.len() == 1 {
+            return vec![offset];
+        }
+
+        let mid = openings.len() / 2;
+        let (left, right) = openings.split_at(mid);
+        let check_half = |half, offset| {
+            if self.check_batch(half) {
+                Vec::new()
+            } else {
+                self.failing_indices(half, offset)
+            }
+        };
+
+        #[cfg(feature = "parallel")]
+        let (mut failed, right_failed) = rayon::join(
+            || check_half(left, offset),
+            || check_half(right, offset + mid),
+        );
+        #[cfg(not(feature = "parallel"))]
+        let (mut failed, right_failed) =
+            (check_half(left, offset), check_half(right, offset + mid));
+
+        failed.extend(right_failed);
+        failed
+    }
+}
+
+/// Computes `prod bases_k^scalars_k` with the bucket method, which for short exponents and many
+/// bases is far cheaper than a full scalar multiplication per base.
+fn short_msm<G: Group>(bases: &[G], scalars: &[u128]) -> G {
+    const BITS: usize = u128::BITS as usize;
+    let window = (bases.len().max(1).ilog2() as usize).clamp(3, 16) - 2;
+    let mask = (1 << window) - 1;
+
+    let mut buckets = vec![G::identity(); mask];
+    let mut acc = G::identity();
+    for w in (0..BITS.div_ceil(window)).rev() {
+        for _ in 0..window {
+            acc = acc.double();
+        }
+
+        buckets.fill(G::identity());
+        for (base, scalar) in zip(bases, scalars) {
+            let digit = (scalar >> (w * window)) as usize & mask;
+            if digit != 0 {
+                buckets[digit - 1] += base;
+            }
+        }
+
+        // Adds `sum_j j * buckets[j - 1]` to the accumulator.
+        let mut running = G::identity();
+        for bucket in buckets.iter().rev() {
+            running += bucket;
+            acc += running;
+        }
+    }
+    acc
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn batch_verify_accepts_valid_openings() {
+        let ck = CommitmentKey::<2>::generate();
+        let openings: Vec<_> = (0..5)
+            .map(|_| {
+                let value = Values::random();
+                let (c, r) = ck.commit(&value);
+                (c, value, r)
+            })
+            .collect();
+
+        assert_eq!(ck.batch_verify(&openings), Ok(()));
+        assert_eq!(ck.batch_verify(&[]), Ok(()));
+    }
+
+    #[test]
+    fn batch_verify_reports_failing_indices() {
+        let ck = CommitmentKey::<2>::generate();
+        let openings: Vec<_> = (0..7)
+            .map(|i| {
+                let value = Values::random();
+                let (c, r) = ck.commit(&value);
+                if i == 2 || i == 5 {
+                    (c, Values::random(), r)
+                } else {
+                    (c, value, r)
+                }
+            })
+            .collect();
+
+        let err = ck.batch_verify(&openings).unwrap_err();
+        assert_eq!(err.failed(), [2, 5]);
+    }
+
+    #[test]
+    fn short_msm_matches_scalar_multiplication() {
+        use bls12_381_plus::{G2Projective, Scalar};
+        use ff::PrimeField;
+
+        let mut rng = thread_rng();
+        for len in [0, 1, 5, 20] {
+            let bases: Vec<G2Projective> =
+                (0..len).map(|_| G2Projective::random(&mut rng)).collect();
+            let scalars: Vec<u128> = (0..len).map(|_| rng.gen()).collect();
+            let expected: G2Projective = zip(&bases, &scalars)
+                .map(|(p, x)| p * Scalar::from_u128(*x))
+                .sum();
+            assert_eq!(short_msm(&bases, &scalars), expected);
+        }
+        let p = G2Projective::random(&mut rng);
+        assert_eq!(
+            short_msm(&[p], &[u128::MAX]),
+            p * Scalar::from_u128(u128::MAX)
+        );
+    }
+}
@@ -0,0 +1,161 @@
+use crate::{CommitmentKey, DynCommitmentKey};
+use bls12_381_plus::elliptic_curve::hash2curve::ExpandMsgXmd;
+use bls12_381_plus::{Bls12, G1Affine, G1Projective};
+use group::Curve;
+
+/// Domain separation tag for hashing to G1, following the suite naming of RFC 9380.
+const DST: &[u8] = b"COMMIT-GROTH09-V01-CS01-with-BLS12381G1_XMD:SHA-256_SSWU_RO_";
+
+/// Identifies which key element is being derived.
+#[derive(Clone, Copy)]
+#[repr(u8)]
+enum Element {
+    G = 0,
+    H = 1,
+    Gr = 2,
+    Hr = 3,
+    Gs = 4,
+    Hs = 5,
+}
+
+impl<const N: usize> CommitmentKey<N, Bls12> {
+    /// Deterministically derives a commitment key from a public label by hashing to G1.
+    ///
+    /// Unlike [`CommitmentKey::generate`], nobody knows the discrete logarithms between the key
+    /// elements, so nobody holds a trapdoor. Any party can rebuild the same key from the same
+    /// `domain_separator`.
+    pub fn derive(domain_separator: &[u8]) -> CommitmentKey<N, Bls12> {
+        let mut g_arr = [G1Affine::identity(); N];
+        let mut h_arr = [G1Affine::identity(); N];
+        hash_elements_from(domain_separator, Element::G, 0, &mut g_arr);
+        hash_elements_from(domain_separator, Element::H, 0, &mut h_arr);
+
+        CommitmentKey {
+            g_arr,
+            h_arr,
+            gr: hash_to_g1(domain_separator, Element::Gr, 0).to_affine(),
+            hr: hash_to_g1(domain_separator, Element::Hr, 0).to_affine(),
+            gs: hash_to_g1(domain_separator, Element::Gs, 0).to_affine(),
+            hs: hash_to_g1(domain_separator, Element::Hs, 0).to_affine(),
+        }
+    }
+
+    /// Extends this key to length `M` by appending derived generators for the indices `N..M`,
+    /// leaving the existing elements unchanged.
+    ///
+    /// Extending a key from [`CommitmentKey::derive`] with the same `domain_separator` gives the
+    /// same key as deriving it at length `M` directly.
+    pub fn extend<const M: usize>(&self, domain_separator: &[u8]) -> CommitmentKey<M, Bls12> {
+        const { assert!(M >= N, "cannot extend a key to a smaller length") };
+        let mut g_arr = [G1Affine::identity(); M];
+        let mut h_arr = [G1Affine::identity(); M];
+        g_arr[..N].copy_from_slice(&self.g_arr);
+        h_arr[..N].copy_from_slice(&self.h_arr);
+        hash_elements_from(domain_separator, Element::G, N, &mut g_arr[N..]);
+        hash_elements_from(domain_separator, Element::H, N, &mut h_arr[N..]);
+
+        CommitmentKey {
+            g_arr,
+            h_arr,
+            gr: self.gr,
+            hr: self.hr,
+            gs: self.gs,
+            hs: self.hs,
+        }
+    }
+}
+
+impl DynCommitmentKey<Bls12> {
+    /// Deterministically derives a commitment key for vectors of length `n`. This is the same key
+    /// as [`CommitmentKey::derive`] produces for `N = n`.
+    pub fn derive(domain_separator: &[u8], n: usize) -> DynCommitmentKey<Bls12> {
+        let mut g_vec = vec![G1Affine::identity(); n];
+        let mut h_vec = vec![G1Affine::identity(); n];
+        hash_elements_from(domain_separator, Element::G, 0, &mut g_vec);
+        hash_elements_from(domain_separator, Element::H, 0, &mut h_vec);
+
+        DynCommitmentKey {
+            g_vec,
+            h_vec,
+            gr: hash_to_g1(domain_separator, Element::Gr, 0).to_affine(),
+            hr: hash_to_g1(domain_separator, Element::Hr, 0).to_affine(),
+            gs: hash_to_g1(domain_separator, Element::Gs, 0).to_affine(),
+            hs: hash_to_g1(domain_separator, Element::Hs, 0).to_affine(),
+        }
+    }
+}
+
+/// Fills `out` with the derived elements at indices `start..start + out.len()`.
+fn hash_elements_from(
+    domain_separator: &[u8],
+    element: Element,
+    start: usize,
+    out: &mut [G1Affine],
+) {
+    let proj: Vec<G1Projective> = (start..start + out.len())
+        .map(|i| hash_to_g1(domain_separator, element, i))
+        .collect();
+    G1Projective::batch_normalize(&proj, out);
+}
+
+/// Hashes `len(domain_separator) || domain_separator || element || index` to G1.
+fn hash_to_g1(domain_separator: &[u8], element: Element, index: usize) -> G1Projective {
+    let mut msg = Vec::with_capacity(8 + domain_separator.len() + 1 + 8);
+    msg.extend_from_slice(&(domain_separator.len() as u64).to_be_bytes());
+    msg.extend_from_slice(domain_separator);
+    msg.push(element as u8);
+    msg.extend_from_slice(&(index as u64).to_be_bytes());
+    G1Projective::hash::<ExpandMsgXmd<sha2::Sha256>>(&msg, DST)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Values;
+
+    #[test]
+    fn derive_is_deterministic() {
+        let ck1 = CommitmentKey::<3>::derive(b"test key");
+        let ck2 = CommitmentKey::<3>::derive(b"test key");
+        assert_eq!(ck1.to_bytes(), ck2.to_bytes());
+
+        let value = Values::random();
+        let (c, r) = ck1.commit(&value);
+        assert_eq!(ck2.verify(&c, &value, &r), Ok(()));
+
+        let ck3 = CommitmentKey::<3>::derive(b"other key");
+        assert_ne!(ck1.to_bytes(), ck3.to_bytes());
+
+        let dyn_ck = DynCommitmentKey::derive(b"test key", 3);
+        assert_eq!(dyn_ck.to_bytes(), ck1.to_bytes());
+    }
+
+    #[test]
+    fn extend_keeps_existing_elements() {
+        let ck = CommitmentKey::<2>::derive(b"extend");
+        let extended = ck.extend::<4>(b"extend");
+        assert_eq!(
+            extended.to_bytes(),
+            CommitmentKey::<4>::derive(b"extend").to_bytes()
+        );
+
+        let value = Values::random();
+        let (c, r) = ck.commit(&value);
+        assert_eq!(extended.verify_slice(&c, &value.values, &r), Ok(()));
+
+        let random_ck = CommitmentKey::<2>::generate();
+        let (c, r) = random_ck.commit(&value);
+        let extended = random_ck.extend::<3>(b"extend");
+        assert_eq!(extended.verify_slice(&c, &value.values, &r), Ok(()));
+    }
+
+    #[test]
+    fn derived_elements_are_distinct() {
+        let ck = CommitmentKey::<2>::derive(b"");
+        let bytes = ck.to_bytes();
+        let mut points: Vec<&[u8]> = bytes[1..].chunks(crate::G1_COMPRESSED_SIZE).collect();
+        points.sort();
+        points.dedup();
+        assert_eq!(points.len(), 2 * 2 + 4);
+    }
+}
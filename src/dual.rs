@@ -0,0 +1,334 @@
+//! The dual variant of the scheme, committing to G1 elements under a commitment key in G2.
+//!
+//! Since the key lives in G2, its elements are kept in prepared form so that the line functions
+//! of the Miller loop are only computed once per key, rather than once per commitment.
+
+use crate::{
+    check_header, decode_key_elem, decode_point, gen_g1_elem, gen_g2_elem, gt_ct_eq, multi_pairing,
+    opening_result, Commitment, Error, FORMAT_VERSION, G1_COMPRESSED_SIZE, G2_COMPRESSED_SIZE,
+};
+use bls12_381_plus::{Bls12, G1Affine, G2Affine};
+use ff::Field;
+use group::prime::PrimeCurveAffine;
+use group::Curve;
+use pairing::{Engine, MultiMillerLoop};
+use rand::thread_rng;
+use rand_core::CryptoRngCore;
+use std::fmt;
+use std::iter::zip;
+use std::ops::Mul;
+
+pub struct ValuesG1<const N: usize, E: Engine = Bls12> {
+    pub(crate) values: [E::G1Affine; N],
+}
+
+impl<const N: usize, E: Engine> ValuesG1<N, E> {
+    pub fn new(values: [E::G1Affine; N]) -> Self {
+        ValuesG1 { values }
+    }
+
+    #[cfg(test)]
+    pub(crate) fn random() -> Self {
+        use group::Group;
+
+        ValuesG1 {
+            values: [(); N].map(|_| E::G1::random(thread_rng()).to_affine()),
+        }
+    }
+}
+
+impl<const N: usize> ValuesG1<N, Bls12> {
+    /// The length in bytes of the encoding produced by [`ValuesG1::to_bytes`].
+    pub const ENCODED_SIZE: usize = 1 + N * G1_COMPRESSED_SIZE;
+
+    /// Decodes values produced by [`ValuesG1::to_bytes`], checking that every point is on the
+    /// curve and in the prime-order subgroup.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, Error> {
+        let body = check_header(bytes, Self::ENCODED_SIZE)?;
+
+        let mut values = [G1Affine::identity(); N];
+        for (value, chunk) in values.iter_mut().zip(body.chunks_exact(G1_COMPRESSED_SIZE)) {
+            *value = decode_point(chunk)?;
+        }
+        Ok(ValuesG1 { values })
+    }
+
+    /// Encodes the values as a version byte followed by the compressed points.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(Self::ENCODED_SIZE);
+        bytes.push(FORMAT_VERSION);
+        for value in &self.values {
+            bytes.extend_from_slice(&value.to_compressed());
+        }
+        bytes
+    }
+}
+
+#[allow(clippy::suspicious_arithmetic_impl)]
+impl<const N: usize, E: Engine> Mul for &ValuesG1<N, E> {
+    type Output = ValuesG1<N, E>;
+
+    fn mul(self, rhs: Self) -> Self::Output {
+        let values =
+            std::array::from_fn(|i| (self.values[i].to_curve() + rhs.values[i]).to_affine());
+        ValuesG1 { values }
+    }
+}
+
+/// The randomness of a commitment to [`ValuesG1`], kept secret like [`Randomness`].
+///
+/// [`Randomness`]: crate::Randomness
+pub struct RandomnessG1<E: Engine = Bls12> {
+    pub(crate) r: E::G1Affine,
+    pub(crate) s: E::G1Affine,
+}
+
+impl<E: Engine> fmt::Debug for RandomnessG1<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RandomnessG1").finish_non_exhaustive()
+    }
+}
+
+impl<E: Engine> RandomnessG1<E> {
+    pub fn gen(rng: &mut impl CryptoRngCore) -> Self {
+        let g = E::G1Affine::generator();
+        let r = gen_g1_elem::<E>(rng, g);
+        let s = gen_g1_elem::<E>(rng, g);
+        RandomnessG1 { r, s }
+    }
+}
+
+#[allow(clippy::suspicious_arithmetic_impl)]
+impl<E: Engine> Mul for &RandomnessG1<E> {
+    type Output = RandomnessG1<E>;
+
+    fn mul(self, rhs: Self) -> Self::Output {
+        let r = (self.r.to_curve() + rhs.r).to_affine();
+        let s = (self.s.to_curve() + rhs.s).to_affine();
+        RandomnessG1 { r, s }
+    }
+}
+
+/// A commitment key with elements in G2, for committing to [`ValuesG1`].
+pub struct CommitmentKeyG1<const N: usize, E: MultiMillerLoop = Bls12> {
+    g_arr: [E::G2Affine; N],
+    h_arr: [E::G2Affine; N],
+    gr: E::G2Affine,
+    hr: E::G2Affine,
+    gs: E::G2Affine,
+    hs: E::G2Affine,
+    pub(crate) prepared: PreparedKey<E>,
+}
+
+/// The key elements of a [`CommitmentKeyG1`] in prepared form.
+///
+/// A prepared element can take up tens of kilobytes, so every element is kept on the heap and
+/// never built as part of an array on the stack.
+pub(crate) struct PreparedKey<E: MultiMillerLoop> {
+    pub(crate) g_arr: Box<[E::G2Prepared]>,
+    pub(crate) h_arr: Box<[E::G2Prepared]>,
+    gr: Box<E::G2Prepared>,
+    hr: Box<E::G2Prepared>,
+    gs: Box<E::G2Prepared>,
+    hs: Box<E::G2Prepared>,
+}
+
+impl<const N: usize, E: MultiMillerLoop> CommitmentKeyG1<N, E> {
+    fn from_elements(
+        g_arr: [E::G2Affine; N],
+        h_arr: [E::G2Affine; N],
+        [gr, hr, gs, hs]: [E::G2Affine; 4],
+    ) -> Self {
+        let prepare =
+            |points: &[E::G2Affine]| points.iter().copied().map(E::G2Prepared::from).collect();
+        let prepared = PreparedKey {
+            g_arr: prepare(&g_arr),
+            h_arr: prepare(&h_arr),
+            gr: Box::new(gr.into()),
+            hr: Box::new(hr.into()),
+            gs: Box::new(gs.into()),
+            hs: Box::new(hs.into()),
+        };
+        CommitmentKeyG1 {
+            g_arr,
+            h_arr,
+            gr,
+            hr,
+            gs,
+            hs,
+            prepared,
+        }
+    }
+
+    pub fn generate() -> CommitmentKeyG1<N, E> {
+        Self::generate_with_rng(&mut thread_rng())
+    }
+
+    /// Generates a commitment key using the supplied cryptographically secure RNG.
+    pub fn generate_with_rng(rng: &mut impl CryptoRngCore) -> CommitmentKeyG1<N, E> {
+        let g = E::G2Affine::generator();
+        let mut gen = || gen_g2_elem::<E>(rng, g);
+
+        let mut g_arr = [g; N];
+        let mut h_arr = [g; N];
+        for (g_i, h_i) in zip(&mut g_arr, &mut h_arr) {
+            *g_i = gen();
+            *h_i = gen();
+        }
+        let others = [gen(), gen(), gen(), gen()];
+
+        Self::from_elements(g_arr, h_arr, others)
+    }
+
+    pub fn commit_with_randomness(
+        &self,
+        value: &ValuesG1<N, E>,
+        randomness: &RandomnessG1<E>,
+    ) -> Commitment<E> {
+        let key = &self.prepared;
+
+        let mut terms = Vec::with_capacity(N + 2);
+        terms.extend([(&randomness.r, &*key.gr), (&randomness.s, &*key.gs)]);
+        terms.extend(zip(&value.values, &key.g_arr));
+        let c = multi_pairing::<E>(&terms);
+
+        terms.clear();
+        terms.extend([(&randomness.r, &*key.hr), (&randomness.s, &*key.hs)]);
+        terms.extend(zip(&value.values, &key.h_arr));
+        let d = multi_pairing::<E>(&terms);
+
+        Commitment { c, d }
+    }
+
+    pub fn commit(&self, value: &ValuesG1<N, E>) -> (Commitment<E>, RandomnessG1<E>) {
+        self.commit_with_rng(value, &mut thread_rng())
+    }
+
+    /// Commits to `value`, sampling the randomness from the supplied cryptographically secure RNG.
+    pub fn commit_with_rng(
+        &self,
+        value: &ValuesG1<N, E>,
+        rng: &mut impl CryptoRngCore,
+    ) -> (Commitment<E>, RandomnessG1<E>) {
+        let randomness = RandomnessG1::gen(rng);
+        let commitment = self.commit_with_randomness(value, &randomness);
+        (commitment, randomness)
+    }
+
+    /// Checks that `value` and `randomness` open `commitment`.
+    ///
+    /// As in [`CommitmentKey::verify`](crate::CommitmentKey::verify), both halves are checked with
+    /// one multi-Miller loop and one final exponentiation. Here the random exponent is applied to
+    /// the G1 opening, so that the prepared key can be used as is.
+    pub fn verify(
+        &self,
+        commitment: &Commitment<E>,
+        value: &ValuesG1<N, E>,
+        randomness: &RandomnessG1<E>,
+    ) -> Result<(), Error> {
+        let a = E::Fr::random(thread_rng());
+        let key = &self.prepared;
+
+        let mut values_a = [E::G1Affine::identity(); N];
+        let values_proj: Vec<E::G1> = value.values.iter().map(|v| *v * a).collect();
+        E::G1::batch_normalize(&values_proj, &mut values_a);
+        let r_a = (randomness.r * a).to_affine();
+        let s_a = (randomness.s * a).to_affine();
+
+        let mut terms = Vec::with_capacity(2 * N + 4);
+        terms.extend([
+            (&randomness.r, &*key.gr),
+            (&randomness.s, &*key.gs),
+            (&r_a, &*key.hr),
+            (&s_a, &*key.hs),
+        ]);
+        terms.extend(zip(&value.values, &key.g_arr));
+        terms.extend(zip(&values_a, &key.h_arr));
+
+        let actual = multi_pairing::<E>(&terms);
+        let expected = commitment.c + commitment.d * a;
+        opening_result(gt_ct_eq::<E>(&actual, &expected))
+    }
+}
+
+impl<const N: usize> CommitmentKeyG1<N, Bls12> {
+    /// The length in bytes of the encoding produced by [`CommitmentKeyG1::to_bytes`].
+    pub const ENCODED_SIZE: usize = 1 + (2 * N + 4) * G2_COMPRESSED_SIZE;
+
+    /// Decodes a key produced by [`CommitmentKeyG1::to_bytes`], checking that every element is
+    /// on the curve, in the prime-order subgroup and not the identity.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, Error> {
+        let body = check_header(bytes, Self::ENCODED_SIZE)?;
+        let mut points = body.chunks_exact(G2_COMPRESSED_SIZE).map(decode_key_elem);
+        let mut next = || points.next().unwrap_or(Err(Error::InvalidEncoding));
+
+        let mut g_arr = [G2Affine::identity(); N];
+        for g in &mut g_arr {
+            *g = next()?;
+        }
+        let mut h_arr = [G2Affine::identity(); N];
+        for h in &mut h_arr {
+            *h = next()?;
+        }
+        let others = [next()?, next()?, next()?, next()?];
+
+        Ok(Self::from_elements(g_arr, h_arr, others))
+    }
+
+    /// Encodes the key as a version byte followed by the compressed points `g_1..g_N`,
+    /// `h_1..h_N`, `g_r`, `h_r`, `g_s` and `h_s`.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(Self::ENCODED_SIZE);
+        bytes.push(FORMAT_VERSION);
+        let points = self.g_arr.iter().chain(&self.h_arr);
+        for point in points.chain([&self.gr, &self.hr, &self.gs, &self.hs]) {
+            bytes.extend_from_slice(&point.to_compressed());
+        }
+        bytes
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn commit_and_verify() {
+        let ck = CommitmentKeyG1::<3>::generate();
+        let value = ValuesG1::random();
+        let (c, r) = ck.commit(&value);
+        assert_eq!(ck.verify(&c, &value, &r), Ok(()));
+        assert_eq!(
+            ck.verify(&c, &ValuesG1::random(), &r),
+            Err(Error::InvalidOpening)
+        );
+
+        let loaded = CommitmentKeyG1::<3>::from_bytes(&ck.to_bytes()).unwrap();
+        assert_eq!(loaded.commit_with_randomness(&value, &r), c);
+        let decoded = ValuesG1::<3>::from_bytes(&value.to_bytes()).unwrap();
+        assert_eq!(ck.verify(&c, &decoded, &r), Ok(()));
+    }
+
+    #[test]
+    fn multiplicatively_homomorphic() {
+        let ck = CommitmentKeyG1::<2>::generate();
+
+        let v1 = ValuesG1::random();
+        let (c1, r1) = ck.commit(&v1);
+
+        let v2 = ValuesG1::random();
+        let (c2, r2) = ck.commit(&v2);
+
+        let expected = ck.commit_with_randomness(&(&v1 * &v2), &(&r1 * &r2));
+        assert_eq!(&c1 * &c2, expected);
+    }
+
+    #[test]
+    fn long_keys_fit_on_the_stack() {
+        let ck = CommitmentKeyG1::<64>::generate();
+        let ck = CommitmentKeyG1::<64>::from_bytes(&ck.to_bytes()).unwrap();
+        let value = ValuesG1::random();
+        let (c, r) = ck.commit(&value);
+        assert_eq!(ck.verify(&c, &value, &r), Ok(()));
+    }
+}
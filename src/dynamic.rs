@@ -0,0 +1,291 @@
+//! Commitment keys and values whose length is only known at runtime.
+
+use crate::{
+    check_header, decode_key_elem, decode_point, gen_g1_elem, Commitment, CommitmentKey, Error,
+    KeyRef, Randomness, Values, FORMAT_VERSION, G1_COMPRESSED_SIZE, G2_COMPRESSED_SIZE,
+};
+use bls12_381_plus::Bls12;
+use group::prime::PrimeCurveAffine;
+use pairing::{Engine, MultiMillerLoop};
+use rand::thread_rng;
+use rand_core::CryptoRngCore;
+
+/// A runtime-sized counterpart of [`Values`].
+pub struct DynValues<E: Engine = Bls12> {
+    values: Vec<E::G2Affine>,
+}
+
+impl<E: Engine> DynValues<E> {
+    pub fn new(values: Vec<E::G2Affine>) -> Self {
+        DynValues { values }
+    }
+
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+}
+
+impl DynValues<Bls12> {
+    /// Decodes values produced by [`DynValues::to_bytes`] or [`Values::to_bytes`]. The number of
+    /// values is implied by the length of the input.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, Error> {
+        let n = bytes.len().saturating_sub(1) / G2_COMPRESSED_SIZE;
+        let body = check_header(bytes, 1 + n * G2_COMPRESSED_SIZE)?;
+        let values = body
+            .chunks_exact(G2_COMPRESSED_SIZE)
+            .map(decode_point)
+            .collect::<Result<_, _>>()?;
+        Ok(DynValues { values })
+    }
+
+    /// Encodes the values in the same format as [`Values::to_bytes`].
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(1 + self.len() * G2_COMPRESSED_SIZE);
+        bytes.push(FORMAT_VERSION);
+        for value in &self.values {
+            bytes.extend_from_slice(&value.to_compressed());
+        }
+        bytes
+    }
+}
+
+impl<const N: usize, E: Engine> From<Values<N, E>> for DynValues<E> {
+    fn from(value: Values<N, E>) -> Self {
+        DynValues {
+            values: value.values.to_vec(),
+        }
+    }
+}
+
+impl<const N: usize, E: Engine> TryFrom<DynValues<E>> for Values<N, E> {
+    type Error = Error;
+
+    fn try_from(value: DynValues<E>) -> Result<Self, Error> {
+        let actual = value.len();
+        let values = value.values.try_into().map_err(|_| Error::LengthMismatch {
+            expected: N,
+            actual,
+        })?;
+        Ok(Values { values })
+    }
+}
+
+/// A runtime-sized counterpart of [`CommitmentKey`].
+pub struct DynCommitmentKey<E: Engine = Bls12> {
+    pub(crate) g_vec: Vec<E::G1Affine>,
+    pub(crate) h_vec: Vec<E::G1Affine>,
+    pub(crate) gr: E::G1Affine,
+    pub(crate) hr: E::G1Affine,
+    pub(crate) gs: E::G1Affine,
+    pub(crate) hs: E::G1Affine,
+}
+
+impl<E: MultiMillerLoop> DynCommitmentKey<E> {
+    /// Generates a commitment key for vectors of length `n`.
+    pub fn generate(n: usize) -> DynCommitmentKey<E> {
+        Self::generate_with_rng(n, &mut thread_rng())
+    }
+
+    /// Generates a commitment key for vectors of length `n` using the supplied cryptographically
+    /// secure RNG.
+    pub fn generate_with_rng(n: usize, rng: &mut impl CryptoRngCore) -> DynCommitmentKey<E> {
+        let g = E::G1Affine::generator();
+
+        let mut g_vec = Vec::with_capacity(n);
+        let mut h_vec = Vec::with_capacity(n);
+        for _ in 0..n {
+            g_vec.push(gen_g1_elem::<E>(rng, g));
+            h_vec.push(gen_g1_elem::<E>(rng, g));
+        }
+
+        let gr = gen_g1_elem::<E>(rng, g);
+        let hr = gen_g1_elem::<E>(rng, g);
+
+        let gs = gen_g1_elem::<E>(rng, g);
+        let hs = gen_g1_elem::<E>(rng, g);
+
+        DynCommitmentKey {
+            g_vec,
+            h_vec,
+            gr,
+            hr,
+            gs,
+            hs,
+        }
+    }
+
+    /// The length of the vectors this key commits to.
+    pub fn len(&self) -> usize {
+        self.g_vec.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.g_vec.is_empty()
+    }
+
+    /// Commits to `value`, failing if it does not have the same length as the key.
+    pub fn commit_with_randomness(
+        &self,
+        value: &DynValues<E>,
+        randomness: &Randomness<E>,
+    ) -> Result<Commitment<E>, Error> {
+        self.check_len(value)?;
+        Ok(self
+            .as_key_ref()
+            .commit_with_randomness(&value.values, randomness))
+    }
+
+    pub fn commit(&self, value: &DynValues<E>) -> Result<(Commitment<E>, Randomness<E>), Error> {
+        self.commit_with_rng(value, &mut thread_rng())
+    }
+
+    /// Commits to `value`, sampling the randomness from the supplied cryptographically secure RNG.
+    pub fn commit_with_rng(
+        &self,
+        value: &DynValues<E>,
+        rng: &mut impl CryptoRngCore,
+    ) -> Result<(Commitment<E>, Randomness<E>), Error> {
+        self.check_len(value)?;
+        let randomness = Randomness::gen(rng);
+        let commitment = self
+            .as_key_ref()
+            .commit_with_randomness(&value.values, &randomness);
+        Ok((commitment, randomness))
+    }
+
+    /// Checks that `value` and `randomness` open `commitment`, as in [`CommitmentKey::verify`].
+    pub fn verify(
+        &self,
+        commitment: &Commitment<E>,
+        value: &DynValues<E>,
+        randomness: &Randomness<E>,
+    ) -> Result<(), Error> {
+        self.check_len(value)?;
+        self.as_key_ref()
+            .verify(commitment, &value.values, randomness)
+    }
+
+    fn check_len(&self, value: &DynValues<E>) -> Result<(), Error> {
+        if value.len() != self.len() {
+            return Err(Error::LengthMismatch {
+                expected: self.len(),
+                actual: value.len(),
+            });
+        }
+        Ok(())
+    }
+
+    fn as_key_ref(&self) -> KeyRef<'_, E> {
+        KeyRef {
+            g_arr: &self.g_vec,
+            h_arr: &self.h_vec,
+            gr: &self.gr,
+            hr: &self.hr,
+            gs: &self.gs,
+            hs: &self.hs,
+        }
+    }
+}
+
+impl DynCommitmentKey<Bls12> {
+    /// Decodes a key produced by [`DynCommitmentKey::to_bytes`] or [`CommitmentKey::to_bytes`],
+    /// checking that every element is on the curve, in the prime-order subgroup and not the
+    /// identity. The length of the key is implied by the length of the input.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, Error> {
+        let n = (bytes.len().saturating_sub(1) / G1_COMPRESSED_SIZE).saturating_sub(4) / 2;
+        let body = check_header(bytes, 1 + (2 * n + 4) * G1_COMPRESSED_SIZE)?;
+        let mut points = body.chunks_exact(G1_COMPRESSED_SIZE).map(decode_key_elem);
+        let mut next = || points.next().unwrap_or(Err(Error::InvalidEncoding));
+
+        let g_vec = (0..n).map(|_| next()).collect::<Result<_, _>>()?;
+        let h_vec = (0..n).map(|_| next()).collect::<Result<_, _>>()?;
+
+        Ok(DynCommitmentKey {
+            g_vec,
+            h_vec,
+            gr: next()?,
+            hr: next()?,
+            gs: next()?,
+            hs: next()?,
+        })
+    }
+
+    /// Encodes the key in the same format as [`CommitmentKey::to_bytes`].
+    pub fn to_bytes(&self) -> Vec<u8> {
+        self.as_key_ref().to_bytes()
+    }
+}
+
+impl<const N: usize, E: Engine> From<CommitmentKey<N, E>> for DynCommitmentKey<E> {
+    fn from(ck: CommitmentKey<N, E>) -> Self {
+        DynCommitmentKey {
+            g_vec: ck.g_arr.to_vec(),
+            h_vec: ck.h_arr.to_vec(),
+            gr: ck.gr,
+            hr: ck.hr,
+            gs: ck.gs,
+            hs: ck.hs,
+        }
+    }
+}
+
+impl<const N: usize, E: Engine> TryFrom<DynCommitmentKey<E>> for CommitmentKey<N, E> {
+    type Error = Error;
+
+    fn try_from(ck: DynCommitmentKey<E>) -> Result<Self, Error> {
+        let mismatch = Error::LengthMismatch {
+            expected: N,
+            actual: ck.g_vec.len(),
+        };
+        Ok(CommitmentKey {
+            g_arr: ck.g_vec.try_into().map_err(|_| mismatch)?,
+            h_arr: ck.h_vec.try_into().map_err(|_| mismatch)?,
+            gr: ck.gr,
+            hr: ck.hr,
+            gs: ck.gs,
+            hs: ck.hs,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dyn_commitment_matches_const_generic() {
+        let ck = CommitmentKey::<3>::generate();
+        let value = Values::<3>::random();
+        let (c, r) = ck.commit(&value);
+
+        let dyn_ck = DynCommitmentKey::from_bytes(&ck.to_bytes()).unwrap();
+        let dyn_value = DynValues::from(value);
+        assert_eq!(dyn_ck.len(), 3);
+        assert_eq!(dyn_ck.commit_with_randomness(&dyn_value, &r), Ok(c));
+
+        let (c, r) = dyn_ck.commit(&dyn_value).unwrap();
+        let ck = CommitmentKey::<3>::try_from(dyn_ck).unwrap();
+        let value = Values::<3>::try_from(dyn_value).unwrap();
+        assert_eq!(ck.verify(&c, &value, &r), Ok(()));
+    }
+
+    #[test]
+    fn length_mismatch_is_rejected() {
+        let dyn_ck = DynCommitmentKey::generate(2);
+        let value = DynValues::from(Values::<3>::random());
+        let mismatch = Error::LengthMismatch {
+            expected: 2,
+            actual: 3,
+        };
+        assert_eq!(dyn_ck.commit(&value).err(), Some(mismatch));
+
+        let decoded = DynValues::from_bytes(&value.to_bytes()).unwrap();
+        assert_eq!(decoded.len(), 3);
+        assert!(Values::<2>::try_from(decoded).is_err());
+        assert!(CommitmentKey::<3>::try_from(dyn_ck).is_err());
+    }
+}
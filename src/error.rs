@@ -0,0 +1,75 @@
+use std::fmt;
+
+/// Errors returned when decoding or validating commitment data.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Error {
+    /// The input did not have the expected length.
+    InvalidLength { expected: usize, actual: usize },
+    /// A vector did not have the length required by the key or type it was used with.
+    LengthMismatch { expected: usize, actual: usize },
+    /// The input was encoded with a format version this crate does not understand.
+    UnsupportedVersion(u8),
+    /// The input did not encode a valid group element.
+    InvalidEncoding,
+    /// The input encoded a point outside the prime-order subgroup.
+    NotInSubgroup,
+    /// A commitment key contained the identity element.
+    IdentityElement,
+    /// The values and randomness do not open the commitment.
+    InvalidOpening,
+    /// A byte message is too long to be committed to under the key.
+    MessageTooLong { max: usize, actual: usize },
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::InvalidLength { expected, actual } => {
+                write!(f, "invalid length: expected {expected} bytes, got {actual}")
+            }
+            Error::LengthMismatch { expected, actual } => {
+                write!(
+                    f,
+                    "length mismatch: expected {expected} elements, got {actual}"
+                )
+            }
+            Error::UnsupportedVersion(version) => {
+                write!(f, "unsupported encoding version {version}")
+            }
+            Error::InvalidEncoding => write!(f, "invalid group element encoding"),
+            Error::NotInSubgroup => write!(f, "group element is not in the prime-order subgroup"),
+            Error::IdentityElement => write!(f, "commitment key contains the identity element"),
+            Error::InvalidOpening => write!(f, "invalid opening of commitment"),
+            Error::MessageTooLong { max, actual } => {
+                write!(f, "message too long: at most {max} bytes, got {actual}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+/// The error returned by [`CommitmentKey::batch_verify`](crate::CommitmentKey::batch_verify).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BatchError {
+    failed: Vec<usize>,
+}
+
+impl BatchError {
+    pub(crate) fn new(failed: Vec<usize>) -> Self {
+        BatchError { failed }
+    }
+
+    /// The indices of the openings that failed to verify, in increasing order.
+    pub fn failed(&self) -> &[usize] {
+        &self.failed
+    }
+}
+
+impl fmt::Display for BatchError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid openings at indices {:?}", self.failed)
+    }
+}
+
+impl std::error::Error for BatchError {}
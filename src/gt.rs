@@ -0,0 +1,195 @@
+//! Encoding of $\mathbb{G}_T$ elements.
+//!
+//! Elements are encoded as the twelve $\mathbb{F}_p$ coefficients of the underlying
+//! $\mathbb{F}_{p^{12}}$ element. The compressed form uses the $T_2$ torus representation:
+//! writing $\mathbb{F}_{p^{12}} = \mathbb{F}_{p^6}[w]/(w^2 - v)$, an element $c_0 + c_1 w$ of norm
+//! one is represented by $m = (1 + c_0) / c_1 \in \mathbb{F}_{p^6}$, and recovered as
+//! $(m + w) / (m - w)$. This halves the size of the encoding. The identity is encoded as $m = 0$,
+//! which would otherwise decode to $-1$, an element outside the order-$r$ subgroup.
+
+use crate::Error;
+use bls12_381_plus::fp::Fp;
+use bls12_381_plus::fp2::Fp2;
+use bls12_381_plus::{Gt, Scalar};
+use group::Group;
+use std::ops::{Add, Mul, Sub};
+
+const FP_SIZE: usize = 48;
+
+pub(crate) const GT_SIZE: usize = 12 * FP_SIZE;
+pub(crate) const GT_COMPRESSED_SIZE: usize = 6 * FP_SIZE;
+
+pub(crate) fn encode(gt: &Gt) -> [u8; GT_SIZE] {
+    gt.to_bytes()
+}
+
+pub(crate) fn decode(bytes: &[u8]) -> Result<Gt, Error> {
+    let bytes = bytes.try_into().map_err(|_| Error::InvalidLength {
+        expected: GT_SIZE,
+        actual: bytes.len(),
+    })?;
+    let gt = Option::<Gt>::from(Gt::from_bytes(bytes)).ok_or(Error::InvalidEncoding)?;
+    check_subgroup(gt)
+}
+
+pub(crate) fn compress(gt: &Gt) -> [u8; GT_COMPRESSED_SIZE] {
+    let bytes = gt.to_bytes();
+    let c0 = Fp6::from_bytes(&bytes[..GT_COMPRESSED_SIZE]).unwrap_or(Fp6::ZERO);
+    let c1 = Fp6::from_bytes(&bytes[GT_COMPRESSED_SIZE..]).unwrap_or(Fp6::ZERO);
+
+    // Only the identity has c1 = 0 in the order-r subgroup, and it maps to m = 0.
+    let m = c1
+        .invert()
+        .map(|c1_inv| (c0 + Fp6::ONE) * c1_inv)
+        .unwrap_or(Fp6::ZERO);
+    m.to_bytes()
+}
+
+pub(crate) fn decompress(bytes: &[u8]) -> Result<Gt, Error> {
+    if bytes.len() != GT_COMPRESSED_SIZE {
+        return Err(Error::InvalidLength {
+            expected: GT_COMPRESSED_SIZE,
+            actual: bytes.len(),
+        });
+    }
+    let m = Fp6::from_bytes(bytes).ok_or(Error::InvalidEncoding)?;
+    if m.is_zero() {
+        return Ok(Gt::IDENTITY);
+    }
+
+    // (m + w) / (m - w) = (m^2 + v + 2mw) / (m^2 - v), and m^2 - v is never zero as v is not a
+    // square in F_{p^6}.
+    let m2 = m * m;
+    let denominator = (m2 - Fp6::V).invert().ok_or(Error::InvalidEncoding)?;
+    let c0 = (m2 + Fp6::V) * denominator;
+    let c1 = (m + m) * denominator;
+
+    let mut encoded = [0u8; GT_SIZE];
+    encoded[..GT_COMPRESSED_SIZE].copy_from_slice(&c0.to_bytes());
+    encoded[GT_COMPRESSED_SIZE..].copy_from_slice(&c1.to_bytes());
+    decode(&encoded)
+}
+
+/// Checks that `gt` has order dividing r, by computing `gt^(r - 1) * gt`.
+fn check_subgroup(gt: Gt) -> Result<Gt, Error> {
+    if bool::from((gt * -Scalar::ONE + gt).is_identity()) {
+        Ok(gt)
+    } else {
+        Err(Error::NotInSubgroup)
+    }
+}
+
+/// The minimal amount of $\mathbb{F}_{p^6} = \mathbb{F}_{p^2}[v]/(v^3 - (u + 1))$ arithmetic
+/// needed for torus compression, as `bls12_381_plus` does not expose its own.
+#[derive(Clone, Copy)]
+struct Fp6([Fp2; 3]);
+
+impl Fp6 {
+    const ZERO: Fp6 = Fp6([Fp2::ZERO; 3]);
+    const ONE: Fp6 = Fp6([Fp2::ONE, Fp2::ZERO, Fp2::ZERO]);
+    const V: Fp6 = Fp6([Fp2::ZERO, Fp2::ONE, Fp2::ZERO]);
+
+    fn from_bytes(bytes: &[u8]) -> Option<Fp6> {
+        let mut fps = bytes.chunks_exact(FP_SIZE).map(|chunk| {
+            let chunk: &[u8; FP_SIZE] = chunk.try_into().ok()?;
+            Option::<Fp>::from(Fp::from_bytes(chunk))
+        });
+        let mut fp2 = || {
+            Some(Fp2 {
+                c0: fps.next()??,
+                c1: fps.next()??,
+            })
+        };
+        Some(Fp6([fp2()?, fp2()?, fp2()?]))
+    }
+
+    fn to_bytes(self) -> [u8; GT_COMPRESSED_SIZE] {
+        let mut bytes = [0u8; GT_COMPRESSED_SIZE];
+        let fps = self.0.iter().flat_map(|c| [c.c0, c.c1]);
+        for (chunk, fp) in bytes.chunks_exact_mut(FP_SIZE).zip(fps) {
+            chunk.copy_from_slice(&fp.to_bytes());
+        }
+        bytes
+    }
+
+    fn is_zero(&self) -> bool {
+        self.0.iter().all(|c| bool::from(c.is_zero()))
+    }
+
+    fn invert(&self) -> Option<Fp6> {
+        let [a0, a1, a2] = self.0;
+        let t0 = a0.square() - (a1 * a2).mul_by_nonresidue();
+        let t1 = a2.square().mul_by_nonresidue() - a0 * a1;
+        let t2 = a1.square() - a0 * a2;
+        let det = a0 * t0 + (a2 * t1 + a1 * t2).mul_by_nonresidue();
+        let det_inv = Option::<Fp2>::from(det.invert())?;
+        Some(Fp6([t0 * det_inv, t1 * det_inv, t2 * det_inv]))
+    }
+}
+
+impl Add for Fp6 {
+    type Output = Fp6;
+
+    fn add(self, rhs: Fp6) -> Fp6 {
+        let [a0, a1, a2] = self.0;
+        let [b0, b1, b2] = rhs.0;
+        Fp6([a0 + b0, a1 + b1, a2 + b2])
+    }
+}
+
+impl Sub for Fp6 {
+    type Output = Fp6;
+
+    fn sub(self, rhs: Fp6) -> Fp6 {
+        let [a0, a1, a2] = self.0;
+        let [b0, b1, b2] = rhs.0;
+        Fp6([a0 - b0, a1 - b1, a2 - b2])
+    }
+}
+
+impl Mul for Fp6 {
+    type Output = Fp6;
+
+    fn mul(self, rhs: Fp6) -> Fp6 {
+        let [a0, a1, a2] = self.0;
+        let [b0, b1, b2] = rhs.0;
+        Fp6([
+            a0 * b0 + (a1 * b2 + a2 * b1).mul_by_nonresidue(),
+            a0 * b1 + a1 * b0 + (a2 * b2).mul_by_nonresidue(),
+            a0 * b2 + a1 * b1 + a2 * b0,
+        ])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bls12_381_plus::{pairing, G1Affine, G2Affine};
+    use ff::Field;
+    use rand::thread_rng;
+
+    fn random_gt() -> Gt {
+        let a = Scalar::random(thread_rng());
+        pairing(&G1Affine::generator(), &G2Affine::generator()) * a
+    }
+
+    #[test]
+    fn compression_roundtrip() {
+        for gt in [Gt::IDENTITY, Gt::generator(), random_gt()] {
+            assert_eq!(decompress(&compress(&gt)).unwrap(), gt);
+            assert_eq!(decode(&encode(&gt)).unwrap(), gt);
+        }
+    }
+
+    #[test]
+    fn decode_rejects_elements_outside_subgroup() {
+        // -1 has order two, so it is a unitary element that is not in the order-r subgroup.
+        let mut minus_one = [0u8; GT_SIZE];
+        minus_one[..FP_SIZE].copy_from_slice(&(-Fp::ONE).to_bytes());
+        assert_eq!(decode(&minus_one), Err(Error::NotInSubgroup));
+
+        let mut random = [0u8; GT_COMPRESSED_SIZE];
+        random[FP_SIZE - 1] = 7;
+        assert_eq!(decompress(&random), Err(Error::NotInSubgroup));
+    }
+}
@@ -1,129 +1,391 @@
 //! [Homomorphic Trapdoor Commitments to Group Elements](https://eprint.iacr.org/2009/007.pdf) as
 //! described by Jens Groth.
 //!
-//! This implementation uses [BLS12-381](https://docs.rs/bls12_381) for the groups and pairing.
+//! The scheme is generic over any pairing engine implementing [`MultiMillerLoop`], and defaults to
+//! [BLS12-381](https://docs.rs/bls12_381_plus). Byte encodings and key derivation are specific to
+//! BLS12-381.
+//!
+//! Decoding, length checks and verification report failures through [`Error`]; no public
+//! function panics on malformed input.
+//!
+//! With the `serde` feature, [`Values`], [`Randomness`], [`Commitment`] and [`CommitmentKey`]
+//! implement `Serialize` and `Deserialize` for BLS12-381, as hex strings in human-readable
+//! formats and as bytes otherwise.
+//!
+//! With the `zeroize` feature, randomness, trapdoors and the secrets held by provers are wiped
+//! from memory when dropped.
+//!
+//! With the `parallel` feature, the Miller loops behind commitments and verification are split
+//! across threads with [rayon](https://docs.rs/rayon). The results are identical to the
+//! sequential ones.
 
-use bls12_381::{pairing, G1Affine, G2Affine, G2Projective, Gt, Scalar};
+mod batch;
+mod derive;
+mod dual;
+mod dynamic;
+mod error;
+mod gt;
+mod message;
+mod mixed;
+mod ops;
+mod prepared;
+mod proofs;
+#[cfg(feature = "zeroize")]
+mod secrets;
+#[cfg(feature = "serde")]
+mod serialization;
+mod trapdoor;
+
+pub use dual::{CommitmentKeyG1, RandomnessG1, ValuesG1};
+pub use dynamic::{DynCommitmentKey, DynValues};
+pub use error::{BatchError, Error};
+pub use mixed::{MixedCommitmentKey, MixedValues};
+pub use prepared::PreparedValues;
+pub use proofs::{
+    EqualityProof, EqualityProver, EqualityResponse, EqualityVerifier, OpeningProof, OpeningProver,
+    OpeningResponse, OpeningVerifier, RerandomizationProof, RerandomizationProver,
+    RerandomizationResponse, RerandomizationVerifier,
+};
+pub use trapdoor::Trapdoor;
+
+use bls12_381_plus::{Bls12, G2Affine};
 use ff::Field;
+use group::prime::PrimeCurveAffine;
+use group::{Curve, Group, GroupEncoding};
+use pairing::{Engine, MillerLoopResult, MultiMillerLoop};
 use rand::prelude::*;
+use rand_core::CryptoRngCore;
+use std::fmt;
 use std::iter::zip;
-use std::ops::{Add, Mul};
+use std::ops::Mul;
+use subtle::{Choice, ConstantTimeEq};
+
+/// Version byte prefixed to every encoding produced by this crate.
+const FORMAT_VERSION: u8 = 1;
+
+const G1_COMPRESSED_SIZE: usize = 48;
+const G2_COMPRESSED_SIZE: usize = 96;
 
-pub struct Values<const N: usize> {
-    values: [G2Affine; N],
+pub struct Values<const N: usize, E: Engine = Bls12> {
+    values: [E::G2Affine; N],
 }
 
-impl<const N: usize> Values<N> {
-    pub fn new(values: [G2Affine; N]) -> Self {
+impl<const N: usize, E: Engine> Values<N, E> {
+    pub fn new(values: [E::G2Affine; N]) -> Self {
         Values { values }
     }
 
-    #[cfg(test)]
-    pub(crate) fn random() -> Self {
-        use group::Group;
-
-        let mut values = Vec::with_capacity(N);
-        for _ in 0..N {
-            values.push(G2Affine::from(G2Projective::random(thread_rng())));
+    /// The vector of identity elements, the neutral element of [`Mul`].
+    pub fn identity() -> Self {
+        Values {
+            values: [E::G2Affine::identity(); N],
         }
+    }
+
+    /// Samples uniformly random values.
+    pub(crate) fn gen(rng: &mut impl CryptoRngCore) -> Self {
+        let g = E::G2Affine::generator();
         Values {
-            values: values.try_into().unwrap(),
+            values: [(); N].map(|_| gen_g2_elem::<E>(rng, g)),
         }
     }
 
-    pub fn from_bytes(_bytes: &[u8]) -> Self {
-        todo!("implement converting to and from bytes")
+    #[cfg(test)]
+    pub(crate) fn random() -> Self {
+        Self::gen(&mut thread_rng())
     }
 
+    /// Raises every value to `exponent`.
+    pub fn pow(&self, exponent: &E::Fr) -> Self {
+        let values = std::array::from_fn(|i| (self.values[i] * exponent).to_affine());
+        Values { values }
+    }
+}
+
+impl<const N: usize, E: Engine> ConstantTimeEq for Values<N, E> {
+    fn ct_eq(&self, other: &Self) -> Choice {
+        zip(&self.values, &other.values)
+            .fold(Choice::from(1), |eq, (a, b)| eq & g2_ct_eq::<E>(a, b))
+    }
+}
+
+/// Compares two G2 elements in constant time by checking whether their quotient is the identity.
+fn g2_ct_eq<E: Engine>(a: &E::G2Affine, b: &E::G2Affine) -> Choice {
+    (a.to_curve() - b).is_identity()
+}
+
+impl<const N: usize> Values<N, Bls12> {
+    /// The length in bytes of the encoding produced by [`Values::to_bytes`].
+    pub const ENCODED_SIZE: usize = 1 + N * G2_COMPRESSED_SIZE;
+
+    /// Decodes values produced by [`Values::to_bytes`], checking that every point is on the
+    /// curve and in the prime-order subgroup.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, Error> {
+        let body = check_header(bytes, Self::ENCODED_SIZE)?;
+
+        let mut values = [G2Affine::identity(); N];
+        for (value, chunk) in values.iter_mut().zip(body.chunks_exact(G2_COMPRESSED_SIZE)) {
+            *value = decode_point(chunk)?;
+        }
+        Ok(Values { values })
+    }
+
+    /// Encodes the values as a version byte followed by the compressed points.
     pub fn to_bytes(&self) -> Vec<u8> {
-        todo!("implement converting to and from bytes")
+        let mut bytes = Vec::with_capacity(Self::ENCODED_SIZE);
+        bytes.push(FORMAT_VERSION);
+        for value in &self.values {
+            bytes.extend_from_slice(&value.to_compressed());
+        }
+        bytes
     }
 }
 
 #[allow(clippy::suspicious_arithmetic_impl)]
-impl<const N: usize> Mul for &Values<N> {
-    type Output = Values<N>;
+impl<const N: usize, E: Engine> Mul for &Values<N, E> {
+    type Output = Values<N, E>;
 
     fn mul(self, rhs: Self) -> Self::Output {
-        let mut values = Vec::with_capacity(N);
-        for i in 0..N {
-            let v = G2Affine::from(self.values[i] + G2Projective::from(rhs.values[i]));
-            values.push(v);
-        }
-        Values {
-            values: values.try_into().unwrap(),
-        }
+        let values =
+            std::array::from_fn(|i| (self.values[i].to_curve() + rhs.values[i]).to_affine());
+        Values { values }
     }
 }
 
-pub struct Randomness {
-    r: G2Affine,
-    s: G2Affine,
+/// The randomness of a commitment, which keeps it hiding and must be kept secret.
+///
+/// It deliberately implements neither `Clone` nor `Copy`, and its `Debug` output is redacted.
+/// With the `zeroize` feature, it is wiped from memory when dropped.
+pub struct Randomness<E: Engine = Bls12> {
+    r: E::G2Affine,
+    s: E::G2Affine,
 }
 
-impl Randomness {
-    pub fn gen(rng: &mut impl RngCore) -> Self {
-        let g = G2Affine::generator();
-        let r = gen_g2_elem(rng, g);
-        let s = gen_g2_elem(rng, g);
+impl<E: Engine> Randomness<E> {
+    pub fn gen(rng: &mut impl CryptoRngCore) -> Self {
+        let g = E::G2Affine::generator();
+        let r = gen_g2_elem::<E>(rng, g);
+        let s = gen_g2_elem::<E>(rng, g);
+        Randomness { r, s }
+    }
+
+    /// Randomness with both elements the identity, the neutral element of [`Mul`]. Committing
+    /// with it is deterministic, so it should only be used to build linear combinations.
+    pub fn zero() -> Self {
+        Randomness {
+            r: E::G2Affine::identity(),
+            s: E::G2Affine::identity(),
+        }
+    }
+
+    /// Raises both elements to `exponent`.
+    pub fn pow(&self, exponent: &E::Fr) -> Self {
+        let r = (self.r * exponent).to_affine();
+        let s = (self.s * exponent).to_affine();
         Randomness { r, s }
     }
 }
 
-impl Mul for &Randomness {
-    type Output = Randomness;
+impl<E: Engine> ConstantTimeEq for Randomness<E> {
+    fn ct_eq(&self, other: &Self) -> Choice {
+        g2_ct_eq::<E>(&self.r, &other.r) & g2_ct_eq::<E>(&self.s, &other.s)
+    }
+}
+
+impl<E: Engine> fmt::Debug for Randomness<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Randomness").finish_non_exhaustive()
+    }
+}
+
+impl Randomness<Bls12> {
+    /// The length in bytes of the encoding produced by [`Randomness::to_bytes`].
+    pub const ENCODED_SIZE: usize = 1 + 2 * G2_COMPRESSED_SIZE;
+
+    /// Decodes randomness produced by [`Randomness::to_bytes`], checking that both points are on
+    /// the curve and in the prime-order subgroup.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, Error> {
+        let body = check_header(bytes, Self::ENCODED_SIZE)?;
+        let (r, s) = body.split_at(G2_COMPRESSED_SIZE);
+        Ok(Randomness {
+            r: decode_point(r)?,
+            s: decode_point(s)?,
+        })
+    }
+
+    /// Encodes the randomness as a version byte followed by the compressed points `r` and `s`.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(Self::ENCODED_SIZE);
+        bytes.push(FORMAT_VERSION);
+        bytes.extend_from_slice(&self.r.to_compressed());
+        bytes.extend_from_slice(&self.s.to_compressed());
+        bytes
+    }
+}
+
+#[allow(clippy::suspicious_arithmetic_impl)]
+impl<E: Engine> Mul for &Randomness<E> {
+    type Output = Randomness<E>;
 
     fn mul(self, rhs: Self) -> Self::Output {
-        let r = self.r.add(&G2Projective::from(rhs.r)).into();
-        let s = self.s.add(&G2Projective::from(rhs.s)).into();
+        let r = (self.r.to_curve() + rhs.r).to_affine();
+        let s = (self.s.to_curve() + rhs.s).to_affine();
         Randomness { r, s }
     }
 }
 
-#[derive(Debug, PartialEq)]
-pub struct Commitment {
-    c: Gt,
-    d: Gt,
+pub struct Commitment<E: Engine = Bls12> {
+    c: E::Gt,
+    d: E::Gt,
+}
+
+impl<E: Engine> fmt::Debug for Commitment<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Commitment")
+            .field("c", &self.c)
+            .field("d", &self.d)
+            .finish()
+    }
+}
+
+impl<E: Engine> ConstantTimeEq for Commitment<E> {
+    fn ct_eq(&self, other: &Self) -> Choice {
+        gt_ct_eq::<E>(&self.c, &other.c) & gt_ct_eq::<E>(&self.d, &other.d)
+    }
+}
+
+impl<E: Engine> PartialEq for Commitment<E> {
+    fn eq(&self, other: &Self) -> bool {
+        self.ct_eq(other).into()
+    }
+}
+
+impl<E: Engine> Commitment<E> {
+    /// The commitment to the identity vector under the zero randomness, for any key.
+    pub fn identity() -> Self {
+        Commitment {
+            c: E::Gt::identity(),
+            d: E::Gt::identity(),
+        }
+    }
+
+    /// Raises both elements to `exponent`, giving a commitment to the values raised to
+    /// `exponent` under the randomness raised to `exponent`.
+    pub fn pow(&self, exponent: &E::Fr) -> Self {
+        Commitment {
+            c: self.c * exponent,
+            d: self.d * exponent,
+        }
+    }
+}
+
+impl<E: MultiMillerLoop> Commitment<E> {
+    /// Multiplies in a fresh commitment to the identity vector, giving an unlinkable commitment
+    /// to the same values. Returns the new commitment and the randomness `delta` it was
+    /// rerandomized with, so that `randomness * delta` opens the new commitment.
+    pub fn rerandomize<const N: usize>(
+        &self,
+        ck: &CommitmentKey<N, E>,
+        rng: &mut impl CryptoRngCore,
+    ) -> (Commitment<E>, Randomness<E>) {
+        let delta = Randomness::gen(rng);
+        let identity = ck.as_key_ref().commit_with_randomness(&[], &delta);
+        (self * &identity, delta)
+    }
 }
 
-impl Mul for &Commitment {
-    type Output = Commitment;
+impl Commitment<Bls12> {
+    /// The length in bytes of the encoding produced by [`Commitment::to_bytes`].
+    pub const ENCODED_SIZE: usize = 1 + 2 * gt::GT_SIZE;
 
-    fn mul(self, rhs: &Commitment) -> Self::Output {
-        let c = self.c.add(rhs.c);
-        let d = self.d.add(rhs.d);
+    /// The length in bytes of the encoding produced by [`Commitment::to_compressed`].
+    pub const COMPRESSED_SIZE: usize = 1 + 2 * gt::GT_COMPRESSED_SIZE;
+
+    /// Decodes a commitment produced by [`Commitment::to_bytes`], checking that both elements
+    /// are in the order-r subgroup of Gt.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, Error> {
+        let body = check_header(bytes, Self::ENCODED_SIZE)?;
+        let (c, d) = body.split_at(gt::GT_SIZE);
+        Ok(Commitment {
+            c: gt::decode(c)?,
+            d: gt::decode(d)?,
+        })
+    }
+
+    /// Encodes the commitment as a version byte followed by both Gt elements in full.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(Self::ENCODED_SIZE);
+        bytes.push(FORMAT_VERSION);
+        bytes.extend_from_slice(&gt::encode(&self.c));
+        bytes.extend_from_slice(&gt::encode(&self.d));
+        bytes
+    }
+
+    /// Decodes a commitment produced by [`Commitment::to_compressed`], checking that both
+    /// elements are in the order-r subgroup of Gt.
+    pub fn from_compressed(bytes: &[u8]) -> Result<Self, Error> {
+        let body = check_header(bytes, Self::COMPRESSED_SIZE)?;
+        let (c, d) = body.split_at(gt::GT_COMPRESSED_SIZE);
+        Ok(Commitment {
+            c: gt::decompress(c)?,
+            d: gt::decompress(d)?,
+        })
+    }
+
+    /// Encodes the commitment as a version byte followed by both Gt elements in torus-compressed
+    /// form, which takes half the space of [`Commitment::to_bytes`].
+    pub fn to_compressed(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(Self::COMPRESSED_SIZE);
+        bytes.push(FORMAT_VERSION);
+        bytes.extend_from_slice(&gt::compress(&self.c));
+        bytes.extend_from_slice(&gt::compress(&self.d));
+        bytes
+    }
+}
+
+#[allow(clippy::suspicious_arithmetic_impl)]
+impl<E: Engine> Mul for &Commitment<E> {
+    type Output = Commitment<E>;
+
+    fn mul(self, rhs: &Commitment<E>) -> Self::Output {
+        let c = self.c + rhs.c;
+        let d = self.d + rhs.d;
         Commitment { c, d }
     }
 }
 
-pub struct CommitmentKey<const N: usize> {
-    g_arr: [G1Affine; N],
-    h_arr: [G1Affine; N],
-    gr: G1Affine,
-    hr: G1Affine,
-    gs: G1Affine,
-    hs: G1Affine,
+pub struct CommitmentKey<const N: usize, E: Engine = Bls12> {
+    g_arr: [E::G1Affine; N],
+    h_arr: [E::G1Affine; N],
+    gr: E::G1Affine,
+    hr: E::G1Affine,
+    gs: E::G1Affine,
+    hs: E::G1Affine,
 }
 
-impl<const N: usize> CommitmentKey<N> {
-    pub fn generate() -> CommitmentKey<N> {
-        let mut rng = thread_rng();
-        let g = G1Affine::generator();
+impl<const N: usize, E: MultiMillerLoop> CommitmentKey<N, E> {
+    pub fn generate() -> CommitmentKey<N, E> {
+        Self::generate_with_rng(&mut thread_rng())
+    }
+
+    /// Generates a commitment key using the supplied cryptographically secure RNG.
+    pub fn generate_with_rng(rng: &mut impl CryptoRngCore) -> CommitmentKey<N, E> {
+        let g = E::G1Affine::generator();
 
-        let mut g_vec = Vec::with_capacity(N);
-        let mut h_vec = Vec::with_capacity(N);
-        for _ in 0..N {
-            g_vec.push(gen_g1_elem(&mut rng, g));
-            h_vec.push(gen_g1_elem(&mut rng, g));
+        // Sampled in the same order as `DynCommitmentKey::generate_with_rng`, so that both give
+        // the same key for the same RNG.
+        let mut g_arr = [g; N];
+        let mut h_arr = [g; N];
+        for (g_i, h_i) in zip(&mut g_arr, &mut h_arr) {
+            *g_i = gen_g1_elem::<E>(rng, g);
+            *h_i = gen_g1_elem::<E>(rng, g);
         }
-        let g_arr = g_vec.try_into().unwrap();
-        let h_arr = h_vec.try_into().unwrap();
 
-        let gr = gen_g1_elem(&mut rng, g);
-        let hr = gen_g1_elem(&mut rng, g);
+        let gr = gen_g1_elem::<E>(rng, g);
+        let hr = gen_g1_elem::<E>(rng, g);
 
-        let gs = gen_g1_elem(&mut rng, g);
-        let hs = gen_g1_elem(&mut rng, g);
+        let gs = gen_g1_elem::<E>(rng, g);
+        let hs = gen_g1_elem::<E>(rng, g);
 
         CommitmentKey {
             g_arr,
@@ -135,40 +397,362 @@ impl<const N: usize> CommitmentKey<N> {
         }
     }
 
-    pub fn commit_with_randomness(&self, value: &Values<N>, randomness: &Randomness) -> Commitment {
-        let mut c = pairing(&self.gr, &randomness.r) + pairing(&self.gs, &randomness.s);
-        for (g, v) in zip(&self.g_arr, &value.values) {
-            c += pairing(g, v)
+    fn as_key_ref(&self) -> KeyRef<'_, E> {
+        KeyRef {
+            g_arr: &self.g_arr,
+            h_arr: &self.h_arr,
+            gr: &self.gr,
+            hr: &self.hr,
+            gs: &self.gs,
+            hs: &self.hs,
         }
+    }
 
-        let mut d = pairing(&self.hr, &randomness.r) + pairing(&self.hs, &randomness.s);
-        for (h, v) in zip(&self.h_arr, &value.values) {
-            d += pairing(h, v)
-        }
+    pub fn commit_with_randomness(
+        &self,
+        value: &Values<N, E>,
+        randomness: &Randomness<E>,
+    ) -> Commitment<E> {
+        self.as_key_ref()
+            .commit_with_randomness(&value.values, randomness)
+    }
 
-        Commitment { c, d }
+    pub fn commit(&self, value: &Values<N, E>) -> (Commitment<E>, Randomness<E>) {
+        self.commit_with_rng(value, &mut thread_rng())
     }
 
-    pub fn commit(&self, value: &Values<N>) -> (Commitment, Randomness) {
-        let randomness = Randomness::gen(&mut thread_rng());
+    /// Commits to `value`, sampling the randomness from the supplied cryptographically secure RNG.
+    pub fn commit_with_rng(
+        &self,
+        value: &Values<N, E>,
+        rng: &mut impl CryptoRngCore,
+    ) -> (Commitment<E>, Randomness<E>) {
+        let randomness = Randomness::gen(rng);
         let commitment = Self::commit_with_randomness(self, value, &randomness);
         (commitment, randomness)
     }
+
+    /// Checks that `value` and `randomness` open `commitment`.
+    ///
+    /// Both halves of the commitment are checked at once: with a random `a`, this computes
+    /// `c * d^a` as a single multi-Miller loop (with `h_arr`, `hr` and `hs` raised to `a`) and
+    /// one final exponentiation, so it is cheaper than recomputing the commitment.
+    pub fn verify(
+        &self,
+        commitment: &Commitment<E>,
+        value: &Values<N, E>,
+        randomness: &Randomness<E>,
+    ) -> Result<(), Error> {
+        self.as_key_ref()
+            .verify(commitment, &value.values, randomness)
+    }
+
+    /// Checks that `value` and `randomness` open `commitment` like [`CommitmentKey::verify`], but
+    /// returns the outcome as a [`Choice`] for use in further constant-time code.
+    pub fn verify_ct(
+        &self,
+        commitment: &Commitment<E>,
+        value: &Values<N, E>,
+        randomness: &Randomness<E>,
+    ) -> Choice {
+        self.as_key_ref()
+            .check(commitment, &value.values, randomness)
+    }
+
+    /// Returns the key for the first `M` elements. Commitments under the truncated key are equal
+    /// to commitments under this key to the same values padded with the identity.
+    pub fn truncate<const M: usize>(&self) -> CommitmentKey<M, E> {
+        const { assert!(M <= N, "cannot truncate a key to a larger length") };
+        CommitmentKey {
+            g_arr: std::array::from_fn(|i| self.g_arr[i]),
+            h_arr: std::array::from_fn(|i| self.h_arr[i]),
+            gr: self.gr,
+            hr: self.hr,
+            gs: self.gs,
+            hs: self.hs,
+        }
+    }
+
+    /// Commits to a vector of at most `N` values, implicitly padded with the identity.
+    pub fn commit_slice_with_randomness(
+        &self,
+        values: &[E::G2Affine],
+        randomness: &Randomness<E>,
+    ) -> Result<Commitment<E>, Error> {
+        check_prefix_len::<N, _>(values)?;
+        Ok(self.as_key_ref().commit_with_randomness(values, randomness))
+    }
+
+    /// Commits to a vector of at most `N` values, implicitly padded with the identity.
+    pub fn commit_slice(
+        &self,
+        values: &[E::G2Affine],
+    ) -> Result<(Commitment<E>, Randomness<E>), Error> {
+        self.commit_slice_with_rng(values, &mut thread_rng())
+    }
+
+    /// Commits to a vector of at most `N` values, sampling the randomness from the supplied
+    /// cryptographically secure RNG.
+    pub fn commit_slice_with_rng(
+        &self,
+        values: &[E::G2Affine],
+        rng: &mut impl CryptoRngCore,
+    ) -> Result<(Commitment<E>, Randomness<E>), Error> {
+        let randomness = Randomness::gen(rng);
+        let commitment = self.commit_slice_with_randomness(values, &randomness)?;
+        Ok((commitment, randomness))
+    }
+
+    /// Checks an opening of a commitment made with [`CommitmentKey::commit_slice`].
+    pub fn verify_slice(
+        &self,
+        commitment: &Commitment<E>,
+        values: &[E::G2Affine],
+        randomness: &Randomness<E>,
+    ) -> Result<(), Error> {
+        check_prefix_len::<N, _>(values)?;
+        self.as_key_ref().verify(commitment, values, randomness)
+    }
+}
+
+impl<const N: usize> CommitmentKey<N, Bls12> {
+    /// The length in bytes of the encoding produced by [`CommitmentKey::to_bytes`].
+    pub const ENCODED_SIZE: usize = 1 + (2 * N + 4) * G1_COMPRESSED_SIZE;
+
+    /// Decodes a key produced by [`CommitmentKey::to_bytes`], checking that every element is on
+    /// the curve, in the prime-order subgroup and not the identity.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, Error> {
+        check_header(bytes, Self::ENCODED_SIZE)?;
+        DynCommitmentKey::from_bytes(bytes)?.try_into()
+    }
+
+    /// Encodes the key as a version byte followed by the compressed points `g_1..g_N`,
+    /// `h_1..h_N`, `g_r`, `h_r`, `g_s` and `h_s`.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        self.as_key_ref().to_bytes()
+    }
+}
+
+fn check_prefix_len<const N: usize, T>(values: &[T]) -> Result<(), Error> {
+    if values.len() > N {
+        return Err(Error::LengthMismatch {
+            expected: N,
+            actual: values.len(),
+        });
+    }
+    Ok(())
+}
+
+/// A borrowed commitment key of any length, shared by [`CommitmentKey`] and
+/// [`DynCommitmentKey`]. Values shorter than the key are implicitly padded with the identity;
+/// callers must check that they are not longer than the key.
+struct KeyRef<'a, E: Engine> {
+    g_arr: &'a [E::G1Affine],
+    h_arr: &'a [E::G1Affine],
+    gr: &'a E::G1Affine,
+    hr: &'a E::G1Affine,
+    gs: &'a E::G1Affine,
+    hs: &'a E::G1Affine,
+}
+
+impl<E: MultiMillerLoop> KeyRef<'_, E> {
+    fn commit_with_randomness(
+        &self,
+        values: &[E::G2Affine],
+        randomness: &Randomness<E>,
+    ) -> Commitment<E> {
+        let values: Vec<E::G2Prepared> = values.iter().copied().map(E::G2Prepared::from).collect();
+        self.commit_prepared(&values, randomness)
+    }
+
+    /// Computes `c` and `d` with one multi-Miller loop and one final exponentiation each, sharing
+    /// the prepared G2 inputs between the two.
+    fn commit_prepared(
+        &self,
+        values: &[E::G2Prepared],
+        randomness: &Randomness<E>,
+    ) -> Commitment<E> {
+        let r = E::G2Prepared::from(randomness.r);
+        let s = E::G2Prepared::from(randomness.s);
+
+        let mut terms = Vec::with_capacity(values.len() + 2);
+        terms.extend([(self.gr, &r), (self.gs, &s)]);
+        terms.extend(zip(self.g_arr, values));
+        let c = multi_pairing::<E>(&terms);
+
+        terms.clear();
+        terms.extend([(self.hr, &r), (self.hs, &s)]);
+        terms.extend(zip(self.h_arr, values));
+        let d = multi_pairing::<E>(&terms);
+
+        Commitment { c, d }
+    }
+
+    fn verify(
+        &self,
+        commitment: &Commitment<E>,
+        values: &[E::G2Affine],
+        randomness: &Randomness<E>,
+    ) -> Result<(), Error> {
+        opening_result(self.check(commitment, values, randomness))
+    }
+
+    fn verify_prepared(
+        &self,
+        commitment: &Commitment<E>,
+        values: &[E::G2Prepared],
+        randomness: &Randomness<E>,
+    ) -> Result<(), Error> {
+        opening_result(self.check_prepared(commitment, values, randomness))
+    }
+
+    fn check(
+        &self,
+        commitment: &Commitment<E>,
+        values: &[E::G2Affine],
+        randomness: &Randomness<E>,
+    ) -> Choice {
+        let values: Vec<E::G2Prepared> = values.iter().copied().map(E::G2Prepared::from).collect();
+        self.check_prepared(commitment, &values, randomness)
+    }
+
+    fn check_prepared(
+        &self,
+        commitment: &Commitment<E>,
+        values: &[E::G2Prepared],
+        randomness: &Randomness<E>,
+    ) -> Choice {
+        let a = E::Fr::random(thread_rng());
+
+        let mut h_a = vec![E::G1Affine::identity(); self.h_arr.len()];
+        let h_proj: Vec<E::G1> = self.h_arr.iter().map(|h| *h * a).collect();
+        E::G1::batch_normalize(&h_proj, &mut h_a);
+        let hr_a = (*self.hr * a).to_affine();
+        let hs_a = (*self.hs * a).to_affine();
+
+        let r = E::G2Prepared::from(randomness.r);
+        let s = E::G2Prepared::from(randomness.s);
+
+        let mut terms = Vec::with_capacity(2 * values.len() + 4);
+        terms.extend([(self.gr, &r), (self.gs, &s), (&hr_a, &r), (&hs_a, &s)]);
+        terms.extend(zip(self.g_arr, values));
+        terms.extend(zip(&h_a, values));
+
+        let actual = multi_pairing::<E>(&terms);
+        let expected = commitment.c + commitment.d * a;
+        gt_ct_eq::<E>(&actual, &expected)
+    }
+}
+
+/// Compares two elements of Gt in constant time, as far as the group operations of the engine
+/// are constant time. Engines are not required to implement `ConstantTimeEq` for Gt, so this
+/// checks whether the quotient is the identity.
+fn gt_ct_eq<E: Engine>(a: &E::Gt, b: &E::Gt) -> Choice {
+    (*a - *b).is_identity()
+}
+
+/// Converts the result of a constant-time opening check into the `Result` returned by `verify`.
+fn opening_result(valid: Choice) -> Result<(), Error> {
+    if bool::from(valid) {
+        Ok(())
+    } else {
+        Err(Error::InvalidOpening)
+    }
+}
+
+/// Computes the product of the pairings of `terms` with a single final exponentiation.
+///
+/// With the `parallel` feature, the Miller loop is split into one chunk of terms per thread and
+/// the partial results are combined before the final exponentiation, so the output is the same
+/// as on a single thread.
+fn multi_pairing<E: MultiMillerLoop>(terms: &[(&E::G1Affine, &E::G2Prepared)]) -> E::Gt {
+    #[cfg(feature = "parallel")]
+    let result = {
+        use rayon::prelude::*;
+
+        let chunk_size = terms.len().div_ceil(rayon::current_num_threads()).max(1);
+        terms
+            .par_chunks(chunk_size)
+            .map(E::multi_miller_loop)
+            .reduce_with(|a, b| a + b)
+            .unwrap_or_else(|| E::multi_miller_loop(&[]))
+    };
+    #[cfg(not(feature = "parallel"))]
+    let result = E::multi_miller_loop(terms);
+
+    result.final_exponentiation()
+}
+
+impl KeyRef<'_, Bls12> {
+    fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(1 + (2 * self.g_arr.len() + 4) * G1_COMPRESSED_SIZE);
+        bytes.push(FORMAT_VERSION);
+        let points = self.g_arr.iter().chain(self.h_arr);
+        for point in points.chain([self.gr, self.hr, self.gs, self.hs]) {
+            bytes.extend_from_slice(&point.to_compressed());
+        }
+        bytes
+    }
 }
 
-fn gen_g1_elem(rng: &mut impl RngCore, generator: G1Affine) -> G1Affine {
-    let r = Scalar::random(rng);
-    (generator * r).into()
+/// Checks the length and version byte of an encoding, returning the remaining bytes.
+fn check_header(bytes: &[u8], expected: usize) -> Result<&[u8], Error> {
+    if bytes.len() != expected {
+        return Err(Error::InvalidLength {
+            expected,
+            actual: bytes.len(),
+        });
+    }
+    match bytes.split_first() {
+        Some((&FORMAT_VERSION, body)) => Ok(body),
+        Some((&version, _)) => Err(Error::UnsupportedVersion(version)),
+        None => Err(Error::InvalidLength {
+            expected,
+            actual: 0,
+        }),
+    }
+}
+
+/// Decodes a compressed point, distinguishing invalid encodings from points outside the
+/// prime-order subgroup.
+fn decode_point<A: GroupEncoding>(bytes: &[u8]) -> Result<A, Error> {
+    let mut repr = A::Repr::default();
+    if bytes.len() != repr.as_ref().len() {
+        return Err(Error::InvalidLength {
+            expected: repr.as_ref().len(),
+            actual: bytes.len(),
+        });
+    }
+    repr.as_mut().copy_from_slice(bytes);
+    if bool::from(A::from_bytes_unchecked(&repr).is_none()) {
+        return Err(Error::InvalidEncoding);
+    }
+    Option::from(A::from_bytes(&repr)).ok_or(Error::NotInSubgroup)
+}
+
+/// Decodes a compressed commitment key element, which must also not be the identity.
+fn decode_key_elem<A: PrimeCurveAffine>(bytes: &[u8]) -> Result<A, Error> {
+    let point: A = decode_point(bytes)?;
+    if bool::from(point.is_identity()) {
+        return Err(Error::IdentityElement);
+    }
+    Ok(point)
+}
+
+fn gen_g1_elem<E: Engine>(rng: &mut impl RngCore, generator: E::G1Affine) -> E::G1Affine {
+    let r = E::Fr::random(rng);
+    (generator * r).to_affine()
 }
 
-fn gen_g2_elem(rng: &mut impl RngCore, generator: G2Affine) -> G2Affine {
-    let r = Scalar::random(rng);
-    (generator * r).into()
+fn gen_g2_elem<E: Engine>(rng: &mut impl RngCore, generator: E::G2Affine) -> E::G2Affine {
+    let r = E::Fr::random(rng);
+    (generator * r).to_affine()
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use bls12_381_plus::{G1Affine, G1Projective, G2Projective};
 
     #[test]
     fn it_works() {
@@ -179,6 +763,157 @@ mod tests {
         assert_eq!(c, d);
     }
 
+    #[test]
+    fn commit_matches_independent_pairings() {
+        let ck = CommitmentKey::<3>::generate();
+        let value = Values::random();
+        let (c, r) = ck.commit(&value);
+
+        let pairing = Bls12::pairing;
+        let mut expected_c = pairing(&ck.gr, &r.r) + pairing(&ck.gs, &r.s);
+        let mut expected_d = pairing(&ck.hr, &r.r) + pairing(&ck.hs, &r.s);
+        for i in 0..3 {
+            expected_c += pairing(&ck.g_arr[i], &value.values[i]);
+            expected_d += pairing(&ck.h_arr[i], &value.values[i]);
+        }
+        assert_eq!(
+            c,
+            Commitment {
+                c: expected_c,
+                d: expected_d
+            }
+        );
+    }
+
+    #[test]
+    fn multi_pairing_matches_single_miller_loop() {
+        use bls12_381_plus::G2Prepared;
+
+        let g1: Vec<G1Affine> = (0..9)
+            .map(|_| G1Projective::random(thread_rng()).into())
+            .collect();
+        let g2: Vec<G2Prepared> = (0..9)
+            .map(|_| G2Affine::from(G2Projective::random(thread_rng())).into())
+            .collect();
+        let terms: Vec<_> = zip(&g1, &g2).collect();
+
+        for n in [0, 1, 2, 9] {
+            let expected = Bls12::multi_miller_loop(&terms[..n]).final_exponentiation();
+            assert_eq!(multi_pairing::<Bls12>(&terms[..n]), expected);
+        }
+    }
+
+    #[test]
+    fn verify_accepts_only_correct_openings() {
+        let ck = CommitmentKey::<3>::generate();
+        let value = Values::random();
+        let (c, r) = ck.commit(&value);
+        assert_eq!(ck.verify(&c, &value, &r), Ok(()));
+
+        let other_value = Values::random();
+        assert_eq!(ck.verify(&c, &other_value, &r), Err(Error::InvalidOpening));
+
+        let other_randomness = Randomness::gen(&mut thread_rng());
+        assert_eq!(
+            ck.verify(&c, &value, &other_randomness),
+            Err(Error::InvalidOpening)
+        );
+
+        // Swapping the halves of the commitment must be caught as well.
+        let swapped = Commitment { c: c.d, d: c.c };
+        assert_eq!(ck.verify(&swapped, &value, &r), Err(Error::InvalidOpening));
+    }
+
+    #[test]
+    fn constant_time_equality() {
+        let ck = CommitmentKey::<2>::generate();
+        let value = Values::random();
+        let (c, r) = ck.commit(&value);
+
+        assert!(bool::from(value.ct_eq(&(&value * &Values::identity()))));
+        assert!(!bool::from(value.ct_eq(&Values::random())));
+        assert!(bool::from(r.ct_eq(&(&r * &Randomness::zero()))));
+        assert!(!bool::from(r.ct_eq(&Randomness::zero())));
+        assert!(bool::from(c.ct_eq(&ck.commit_with_randomness(&value, &r))));
+        assert!(!bool::from(c.ct_eq(&Commitment::identity())));
+
+        assert!(bool::from(ck.verify_ct(&c, &value, &r)));
+        assert!(!bool::from(ck.verify_ct(&c, &value, &Randomness::zero())));
+    }
+
+    #[test]
+    fn randomness_debug_is_redacted() {
+        let r = Randomness::<Bls12>::gen(&mut thread_rng());
+        assert_eq!(format!("{r:?}"), "Randomness { .. }");
+    }
+
+    #[test]
+    fn seeded_rng_is_reproducible() {
+        let value = Values::<2>::random();
+
+        let mut rng = StdRng::seed_from_u64(42);
+        let ck1 = CommitmentKey::<2>::generate_with_rng(&mut rng);
+        let (c1, _) = ck1.commit_with_rng(&value, &mut rng);
+
+        let mut rng = StdRng::seed_from_u64(42);
+        let ck2 = CommitmentKey::<2>::generate_with_rng(&mut rng);
+        let (c2, r2) = ck2.commit_with_rng(&value, &mut rng);
+
+        assert_eq!(ck1.to_bytes(), ck2.to_bytes());
+        assert_eq!(c1, c2);
+
+        let dyn_ck = DynCommitmentKey::generate_with_rng(2, &mut StdRng::seed_from_u64(42));
+        assert_eq!(dyn_ck.to_bytes(), ck1.to_bytes());
+        assert_eq!(ck1.verify(&c1, &value, &r2), Ok(()));
+
+        let (c3, _) = ck1
+            .commit_slice_with_rng(&value.values, &mut StdRng::seed_from_u64(7))
+            .unwrap();
+        let (c4, _) = ck1.commit_with_rng(&value, &mut StdRng::seed_from_u64(7));
+        assert_eq!(c3, c4);
+    }
+
+    #[test]
+    fn truncated_key_commits_to_padded_values() {
+        let ck = CommitmentKey::<4>::generate();
+        let short = Values::<2>::random();
+        let (c, r) = ck.truncate::<2>().commit(&short);
+
+        let padded = Values::new([
+            short.values[0],
+            short.values[1],
+            G2Affine::identity(),
+            G2Affine::identity(),
+        ]);
+        assert_eq!(ck.verify(&c, &padded, &r), Ok(()));
+        assert_eq!(ck.verify_slice(&c, &short.values, &r), Ok(()));
+        assert_eq!(ck.commit_slice_with_randomness(&short.values, &r), Ok(c));
+
+        let long = Values::<5>::random();
+        assert_eq!(
+            ck.commit_slice(&long.values).err(),
+            Some(Error::LengthMismatch {
+                expected: 4,
+                actual: 5
+            })
+        );
+    }
+
+    #[test]
+    fn works_with_other_pairing_engines() {
+        type E = bls12_381::Bls12;
+        let ck = CommitmentKey::<3, E>::generate();
+
+        let v1 = Values::<3, E>::random();
+        let (c1, r1) = ck.commit(&v1);
+        assert_eq!(ck.verify(&c1, &v1, &r1), Ok(()));
+
+        let v2 = Values::random();
+        let (c2, r2) = ck.commit(&v2);
+        let expected = ck.commit_with_randomness(&(&v1 * &v2), &(&r1 * &r2));
+        assert_eq!(&c1 * &c2, expected);
+    }
+
     #[test]
     fn multiplicatively_homomorphic() {
         let ck = CommitmentKey::<1>::generate();
@@ -197,4 +932,103 @@ mod tests {
 
         assert_eq!(actual, expected);
     }
+
+    #[test]
+    fn values_bytes_roundtrip() {
+        let ck = CommitmentKey::<3>::generate();
+        let value = Values::<3>::random();
+        let bytes = value.to_bytes();
+        assert_eq!(bytes.len(), Values::<3>::ENCODED_SIZE);
+
+        let decoded = Values::<3>::from_bytes(&bytes).unwrap();
+        let (c, r) = ck.commit(&value);
+        assert_eq!(c, ck.commit_with_randomness(&decoded, &r));
+    }
+
+    #[test]
+    fn values_from_bytes_rejects_invalid_input() {
+        let bytes = Values::<2>::random().to_bytes();
+
+        assert_eq!(
+            Values::<3>::from_bytes(&bytes).err(),
+            Some(Error::InvalidLength {
+                expected: Values::<3>::ENCODED_SIZE,
+                actual: bytes.len()
+            })
+        );
+
+        let mut wrong_version = bytes.clone();
+        wrong_version[0] = FORMAT_VERSION + 1;
+        assert_eq!(
+            Values::<2>::from_bytes(&wrong_version).err(),
+            Some(Error::UnsupportedVersion(FORMAT_VERSION + 1))
+        );
+
+        let mut not_a_point = bytes.clone();
+        not_a_point[1..1 + G2_COMPRESSED_SIZE].copy_from_slice(&[0xff; G2_COMPRESSED_SIZE]);
+        assert_eq!(
+            Values::<2>::from_bytes(&not_a_point).err(),
+            Some(Error::InvalidEncoding)
+        );
+    }
+
+    #[test]
+    fn commitment_bytes_roundtrip() {
+        let ck = CommitmentKey::<2>::generate();
+        let (c, _) = ck.commit(&Values::random());
+
+        let bytes = c.to_bytes();
+        assert_eq!(bytes.len(), Commitment::ENCODED_SIZE);
+        assert_eq!(Commitment::from_bytes(&bytes).unwrap(), c);
+
+        let compressed = c.to_compressed();
+        assert_eq!(compressed.len(), Commitment::COMPRESSED_SIZE);
+        assert_eq!(Commitment::from_compressed(&compressed).unwrap(), c);
+
+        assert!(Commitment::from_bytes(&compressed).is_err());
+        assert!(Commitment::from_compressed(&bytes).is_err());
+    }
+
+    #[test]
+    fn commitment_key_bytes_roundtrip() {
+        let ck = CommitmentKey::<3>::generate();
+        let bytes = ck.to_bytes();
+        assert_eq!(bytes.len(), CommitmentKey::<3>::ENCODED_SIZE);
+
+        let loaded = CommitmentKey::<3>::from_bytes(&bytes).unwrap();
+        let value = Values::random();
+        let (c, r) = ck.commit(&value);
+        assert_eq!(c, loaded.commit_with_randomness(&value, &r));
+    }
+
+    #[test]
+    fn commitment_key_from_bytes_rejects_identity() {
+        let mut bytes = CommitmentKey::<2>::generate().to_bytes();
+        let hs = bytes.len() - G1_COMPRESSED_SIZE;
+        bytes[hs..].copy_from_slice(&G1Affine::identity().to_compressed());
+        assert_eq!(
+            CommitmentKey::<2>::from_bytes(&bytes).err(),
+            Some(Error::IdentityElement)
+        );
+    }
+
+    #[test]
+    fn values_from_bytes_rejects_points_outside_subgroup() {
+        // Search for a compressed encoding that is on the curve but has a cofactor component.
+        let mut bytes = Values::<1>::random().to_bytes();
+        for x in 1u8.. {
+            let mut encoding = [0u8; G2_COMPRESSED_SIZE];
+            encoding[0] = 0x80;
+            encoding[G2_COMPRESSED_SIZE - 1] = x;
+            let point = G2Affine::from_compressed_unchecked(&encoding);
+            if bool::from(point.is_some()) && !bool::from(point.unwrap().is_torsion_free()) {
+                bytes[1..].copy_from_slice(&encoding);
+                break;
+            }
+        }
+        assert_eq!(
+            Values::<1>::from_bytes(&bytes).err(),
+            Some(Error::NotInSubgroup)
+        );
+    }
 }
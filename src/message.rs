@@ -0,0 +1,136 @@
+//! Commitments to byte messages.
+
+use crate::{Commitment, CommitmentKey, Error, Randomness, Values};
+use bls12_381_plus::{Bls12, G2Affine, G2Projective, Scalar};
+use rand::thread_rng;
+use rand_core::CryptoRngCore;
+
+/// The number of message bytes embedded in each G2 element.
+const CHUNK_SIZE: usize = 31;
+
+impl<const N: usize> Values<N, Bls12> {
+    /// The length in bytes of the longest message that [`Values::from_message`] can embed. No
+    /// message fits when `N` is zero.
+    pub const MAX_MESSAGE_LEN: usize = N.saturating_sub(1) * CHUNK_SIZE;
+
+    /// Embeds a byte message as the vector `g2^len, g2^m_1, g2^m_2, ...`, where `len` is the
+    /// length of the message in bytes and `m_i` are its consecutive 31-byte chunks read as
+    /// little-endian scalars, with the last one zero-padded. The rest of the vector is filled
+    /// with the identity.
+    ///
+    /// The chunks are small enough to always be canonical scalars, and the length prefix keeps
+    /// messages that differ only in trailing zero bytes from colliding.
+    pub fn from_message(message: &[u8]) -> Result<Self, Error> {
+        // The length prefix needs a slot even for an empty message.
+        if N == 0 || message.len() > Self::MAX_MESSAGE_LEN {
+            return Err(Error::MessageTooLong {
+                max: Self::MAX_MESSAGE_LEN,
+                actual: message.len(),
+            });
+        }
+
+        let g = G2Affine::generator();
+        let mut proj = [G2Projective::IDENTITY; N];
+        proj[0] = g * Scalar::from(message.len() as u64);
+        for (p, chunk) in proj[1..].iter_mut().zip(message.chunks(CHUNK_SIZE)) {
+            let mut wide = [0u8; 64];
+            wide[..chunk.len()].copy_from_slice(chunk);
+            *p = g * Scalar::from_bytes_wide(&wide);
+        }
+
+        let mut values = [G2Affine::identity(); N];
+        G2Projective::batch_normalize(&proj, &mut values);
+        Ok(Values::new(values))
+    }
+}
+
+impl<const N: usize> CommitmentKey<N, Bls12> {
+    /// Commits to a byte message of at most [`Values::MAX_MESSAGE_LEN`] bytes, embedded as
+    /// described in [`Values::from_message`].
+    pub fn commit_bytes(
+        &self,
+        message: &[u8],
+    ) -> Result<(Commitment<Bls12>, Randomness<Bls12>), Error> {
+        self.commit_bytes_with_rng(message, &mut thread_rng())
+    }
+
+    /// Commits to a byte message, sampling the randomness from the supplied cryptographically
+    /// secure RNG.
+    pub fn commit_bytes_with_rng(
+        &self,
+        message: &[u8],
+        rng: &mut impl CryptoRngCore,
+    ) -> Result<(Commitment<Bls12>, Randomness<Bls12>), Error> {
+        let value = Values::from_message(message)?;
+        Ok(self.commit_with_rng(&value, rng))
+    }
+
+    /// Checks that `message` and `randomness` open `commitment`, returning the embedded values
+    /// so that the commitment can be used with the rest of the API.
+    pub fn open_bytes(
+        &self,
+        commitment: &Commitment<Bls12>,
+        message: &[u8],
+        randomness: &Randomness<Bls12>,
+    ) -> Result<Values<N, Bls12>, Error> {
+        let value = Values::from_message(message)?;
+        self.verify(commitment, &value, randomness)?;
+        Ok(value)
+    }
+
+    /// Checks that `message` and `randomness` open `commitment`.
+    pub fn verify_bytes(
+        &self,
+        commitment: &Commitment<Bls12>,
+        message: &[u8],
+        randomness: &Randomness<Bls12>,
+    ) -> Result<(), Error> {
+        self.open_bytes(commitment, message, randomness).map(|_| ())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn commit_and_verify_bytes() {
+        let ck = CommitmentKey::<4>::generate();
+        let message = b"a message spanning more than one chunk of the embedding";
+        let (c, r) = ck.commit_bytes(message).unwrap();
+        assert_eq!(ck.verify_bytes(&c, message, &r), Ok(()));
+
+        let value = ck.open_bytes(&c, message, &r).unwrap();
+        assert_eq!(ck.verify(&c, &value, &r), Ok(()));
+        assert_eq!(
+            ck.verify_bytes(&c, b"another message", &r),
+            Err(Error::InvalidOpening)
+        );
+    }
+
+    #[test]
+    fn trailing_zeros_change_the_commitment() {
+        let ck = CommitmentKey::<2>::generate();
+        let (c, r) = ck.commit_bytes(b"abc").unwrap();
+        assert_eq!(
+            ck.verify_bytes(&c, b"abc\0", &r),
+            Err(Error::InvalidOpening)
+        );
+        assert_eq!(ck.verify_bytes(&c, b"", &r), Err(Error::InvalidOpening));
+    }
+
+    #[test]
+    fn long_messages_are_rejected() {
+        let ck = CommitmentKey::<3>::generate();
+        let max = Values::<3>::MAX_MESSAGE_LEN;
+        assert!(ck.commit_bytes(&vec![0xff; max]).is_ok());
+        assert_eq!(
+            ck.commit_bytes(&vec![0xff; max + 1]).err(),
+            Some(Error::MessageTooLong {
+                max,
+                actual: max + 1
+            })
+        );
+        assert!(CommitmentKey::<0>::generate().commit_bytes(b"").is_err());
+    }
+}
@@ -0,0 +1,267 @@
+//! Commitments to a vector of G2 elements and a vector of G1 elements at once.
+//!
+//! The key combines a [`CommitmentKey`] for the G2 part with a [`CommitmentKeyG1`] for the G1
+//! part, whose mirrored elements `g_j` and `h_j` in G2 are written `u_j` and `v_j` here, giving
+//! `c = e(gr, r) e(gs, s) prod e(g_i, x_i) prod e(y_j, u_j)` and
+//! `d = e(hr, r) e(hs, s) prod e(h_i, x_i) prod e(y_j, v_j)`.
+//! The randomness `(r, s)` in G2 hides both parts, so the randomness elements of the
+//! [`CommitmentKeyG1`] are not used.
+
+use crate::{
+    gt_ct_eq, multi_pairing, opening_result, Commitment, CommitmentKey, CommitmentKeyG1, Error,
+    Randomness, Values, ValuesG1,
+};
+use bls12_381_plus::Bls12;
+use ff::Field;
+use group::prime::PrimeCurveAffine;
+use group::Curve;
+use pairing::{Engine, MultiMillerLoop};
+use rand::thread_rng;
+use rand_core::CryptoRngCore;
+use std::iter::zip;
+use std::ops::Mul;
+
+/// A vector of `N` G2 elements together with a vector of `M` G1 elements.
+pub struct MixedValues<const N: usize, const M: usize, E: Engine = Bls12> {
+    g2: Values<N, E>,
+    g1: ValuesG1<M, E>,
+}
+
+impl<const N: usize, const M: usize, E: Engine> MixedValues<N, M, E> {
+    pub fn new(g2: Values<N, E>, g1: ValuesG1<M, E>) -> Self {
+        MixedValues { g2, g1 }
+    }
+
+    #[cfg(test)]
+    pub(crate) fn random() -> Self {
+        MixedValues {
+            g2: Values::random(),
+            g1: ValuesG1::random(),
+        }
+    }
+}
+
+impl<const N: usize, const M: usize, E: Engine> Mul for &MixedValues<N, M, E> {
+    type Output = MixedValues<N, M, E>;
+
+    fn mul(self, rhs: Self) -> Self::Output {
+        MixedValues {
+            g2: &self.g2 * &rhs.g2,
+            g1: &self.g1 * &rhs.g1,
+        }
+    }
+}
+
+pub struct MixedCommitmentKey<const N: usize, const M: usize, E: MultiMillerLoop = Bls12> {
+    key: CommitmentKey<N, E>,
+    key_g1: CommitmentKeyG1<M, E>,
+}
+
+impl<const N: usize, const M: usize, E: MultiMillerLoop> MixedCommitmentKey<N, M, E> {
+    pub fn generate() -> MixedCommitmentKey<N, M, E> {
+        Self::generate_with_rng(&mut thread_rng())
+    }
+
+    /// Generates a commitment key using the supplied cryptographically secure RNG.
+    pub fn generate_with_rng(rng: &mut impl CryptoRngCore) -> MixedCommitmentKey<N, M, E> {
+        let key = CommitmentKey::generate_with_rng(rng);
+        let key_g1 = CommitmentKeyG1::generate_with_rng(rng);
+        MixedCommitmentKey { key, key_g1 }
+    }
+
+    /// Combines a key for the G2 part and a key for the G1 part into a mixed key.
+    pub fn from_keys(key: CommitmentKey<N, E>, key_g1: CommitmentKeyG1<M, E>) -> Self {
+        MixedCommitmentKey { key, key_g1 }
+    }
+
+    pub fn commit_with_randomness(
+        &self,
+        value: &MixedValues<N, M, E>,
+        randomness: &Randomness<E>,
+    ) -> Commitment<E> {
+        let key = &self.key;
+        let key_g1 = &self.key_g1.prepared;
+        let r = E::G2Prepared::from(randomness.r);
+        let s = E::G2Prepared::from(randomness.s);
+        let x: Vec<E::G2Prepared> = value
+            .g2
+            .values
+            .iter()
+            .copied()
+            .map(E::G2Prepared::from)
+            .collect();
+        let y = &value.g1.values;
+
+        let mut terms = Vec::with_capacity(N + M + 2);
+        terms.extend([(&key.gr, &r), (&key.gs, &s)]);
+        terms.extend(zip(&key.g_arr, &x));
+        terms.extend(zip(y, &key_g1.g_arr));
+        let c = multi_pairing::<E>(&terms);
+
+        terms.clear();
+        terms.extend([(&key.hr, &r), (&key.hs, &s)]);
+        terms.extend(zip(&key.h_arr, &x));
+        terms.extend(zip(y, &key_g1.h_arr));
+        let d = multi_pairing::<E>(&terms);
+
+        Commitment { c, d }
+    }
+
+    pub fn commit(&self, value: &MixedValues<N, M, E>) -> (Commitment<E>, Randomness<E>) {
+        self.commit_with_rng(value, &mut thread_rng())
+    }
+
+    /// Commits to `value`, sampling the randomness from the supplied cryptographically secure RNG.
+    pub fn commit_with_rng(
+        &self,
+        value: &MixedValues<N, M, E>,
+        rng: &mut impl CryptoRngCore,
+    ) -> (Commitment<E>, Randomness<E>) {
+        let randomness = Randomness::gen(rng);
+        let commitment = self.commit_with_randomness(value, &randomness);
+        (commitment, randomness)
+    }
+
+    /// Checks that `value` and `randomness` open `commitment`, with one multi-Miller loop and one
+    /// final exponentiation as in [`CommitmentKey::verify`].
+    pub fn verify(
+        &self,
+        commitment: &Commitment<E>,
+        value: &MixedValues<N, M, E>,
+        randomness: &Randomness<E>,
+    ) -> Result<(), Error> {
+        let a = E::Fr::random(thread_rng());
+        let key = &self.key;
+        let key_g1 = &self.key_g1.prepared;
+
+        // Raise the G1 side of every term of d to a.
+        let g1_a: Vec<E::G1> = (key.h_arr.iter().chain(&value.g1.values))
+            .chain([&key.hr, &key.hs])
+            .map(|p| *p * a)
+            .collect();
+        let mut g1_a_affine = vec![E::G1Affine::identity(); g1_a.len()];
+        E::G1::batch_normalize(&g1_a, &mut g1_a_affine);
+        let (h_a, rest) = g1_a_affine.split_at(N);
+        let (y_a, rest) = rest.split_at(M);
+        let (hr_a, hs_a) = (&rest[0], &rest[1]);
+
+        let r = E::G2Prepared::from(randomness.r);
+        let s = E::G2Prepared::from(randomness.s);
+        let x: Vec<E::G2Prepared> = value
+            .g2
+            .values
+            .iter()
+            .copied()
+            .map(E::G2Prepared::from)
+            .collect();
+
+        let mut terms = Vec::with_capacity(2 * (N + M + 2));
+        terms.extend([(&key.gr, &r), (&key.gs, &s), (hr_a, &r), (hs_a, &s)]);
+        terms.extend(zip(&key.g_arr, &x));
+        terms.extend(zip(&value.g1.values, &key_g1.g_arr));
+        terms.extend(zip(h_a, &x));
+        terms.extend(zip(y_a, &key_g1.h_arr));
+
+        let actual = multi_pairing::<E>(&terms);
+        let expected = commitment.c + commitment.d * a;
+        opening_result(gt_ct_eq::<E>(&actual, &expected))
+    }
+}
+
+impl<const N: usize, const M: usize> MixedCommitmentKey<N, M, Bls12> {
+    /// The length in bytes of the encoding produced by [`MixedCommitmentKey::to_bytes`].
+    pub const ENCODED_SIZE: usize =
+        CommitmentKey::<N>::ENCODED_SIZE + CommitmentKeyG1::<M>::ENCODED_SIZE;
+
+    /// Decodes a key produced by [`MixedCommitmentKey::to_bytes`], checking every element as
+    /// [`CommitmentKey::from_bytes`] and [`CommitmentKeyG1::from_bytes`] do.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, Error> {
+        if bytes.len() != Self::ENCODED_SIZE {
+            return Err(Error::InvalidLength {
+                expected: Self::ENCODED_SIZE,
+                actual: bytes.len(),
+            });
+        }
+        let (key, key_g1) = bytes.split_at(CommitmentKey::<N>::ENCODED_SIZE);
+        Ok(MixedCommitmentKey {
+            key: CommitmentKey::from_bytes(key)?,
+            key_g1: CommitmentKeyG1::from_bytes(key_g1)?,
+        })
+    }
+
+    /// Encodes the key as the encoding of its [`CommitmentKey`] followed by the encoding of its
+    /// [`CommitmentKeyG1`].
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = self.key.to_bytes();
+        bytes.extend_from_slice(&self.key_g1.to_bytes());
+        bytes
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn commit_and_verify() {
+        let ck = MixedCommitmentKey::<2, 3>::generate();
+        let value = MixedValues::random();
+        let (c, r) = ck.commit(&value);
+        assert_eq!(ck.verify(&c, &value, &r), Ok(()));
+        assert_eq!(ck.commit_with_randomness(&value, &r), c);
+
+        let other = MixedValues::random();
+        assert_eq!(ck.verify(&c, &other, &r), Err(Error::InvalidOpening));
+    }
+
+    #[test]
+    fn multiplicatively_homomorphic() {
+        let ck = MixedCommitmentKey::<1, 1>::generate();
+
+        let v1 = MixedValues::random();
+        let (c1, r1) = ck.commit(&v1);
+
+        let v2 = MixedValues::random();
+        let (c2, r2) = ck.commit(&v2);
+
+        let v_mul = &v1 * &v2;
+        let r_mul = &r1 * &r2;
+        let expected = ck.commit_with_randomness(&v_mul, &r_mul);
+
+        let actual = c1.mul(&c2);
+
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn key_bytes_roundtrip() {
+        let ck = MixedCommitmentKey::<2, 3>::generate();
+        let bytes = ck.to_bytes();
+        assert_eq!(bytes.len(), MixedCommitmentKey::<2, 3>::ENCODED_SIZE);
+
+        let decoded = MixedCommitmentKey::<2, 3>::from_bytes(&bytes).unwrap();
+        assert_eq!(decoded.to_bytes(), bytes);
+        let value = MixedValues::random();
+        let (c, r) = ck.commit(&value);
+        assert_eq!(decoded.verify(&c, &value, &r), Ok(()));
+
+        assert_eq!(
+            MixedCommitmentKey::<2, 2>::from_bytes(&bytes).err(),
+            Some(Error::InvalidLength {
+                expected: MixedCommitmentKey::<2, 2>::ENCODED_SIZE,
+                actual: bytes.len()
+            })
+        );
+        let mut corrupted = bytes.clone();
+        corrupted[CommitmentKey::<2>::ENCODED_SIZE + 1] ^= 0x1f;
+        assert!(MixedCommitmentKey::<2, 3>::from_bytes(&corrupted).is_err());
+    }
+
+    #[test]
+    fn long_values_fit_on_the_stack() {
+        let ck = MixedCommitmentKey::<64, 1>::generate();
+        let value = MixedValues::random();
+        let (c, r) = ck.commit(&value);
+        assert_eq!(ck.verify(&c, &value, &r), Ok(()));
+    }
+}
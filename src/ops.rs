@@ -0,0 +1,231 @@
+//! The remaining group operations on [`Values`], [`Randomness`] and [`Commitment`].
+//!
+//! The group law on each type is written multiplicatively, matching the homomorphism
+//! `com(v1; r1) * com(v2; r2) = com(v1 * v2; r1 * r2)`. Only the operations on references are
+//! implemented directly; the owned and assigning forms forward to them.
+
+use crate::{Commitment, Randomness, Values};
+use group::prime::PrimeCurveAffine;
+use group::Curve;
+use pairing::Engine;
+use std::iter::Product;
+use std::ops::{Div, DivAssign, Mul, MulAssign, Neg};
+
+#[allow(clippy::suspicious_arithmetic_impl)]
+impl<const N: usize, E: Engine> Div for &Values<N, E> {
+    type Output = Values<N, E>;
+
+    fn div(self, rhs: Self) -> Self::Output {
+        let values =
+            std::array::from_fn(|i| (self.values[i].to_curve() - rhs.values[i]).to_affine());
+        Values { values }
+    }
+}
+
+impl<const N: usize, E: Engine> Neg for &Values<N, E> {
+    type Output = Values<N, E>;
+
+    /// Returns the inverse of the values.
+    fn neg(self) -> Self::Output {
+        let values = self.values.map(|v| -v);
+        Values { values }
+    }
+}
+
+#[allow(clippy::suspicious_arithmetic_impl)]
+impl<E: Engine> Div for &Randomness<E> {
+    type Output = Randomness<E>;
+
+    fn div(self, rhs: Self) -> Self::Output {
+        let r = (self.r.to_curve() - rhs.r).to_affine();
+        let s = (self.s.to_curve() - rhs.s).to_affine();
+        Randomness { r, s }
+    }
+}
+
+impl<E: Engine> Neg for &Randomness<E> {
+    type Output = Randomness<E>;
+
+    /// Returns the inverse of the randomness.
+    fn neg(self) -> Self::Output {
+        Randomness {
+            r: -self.r,
+            s: -self.s,
+        }
+    }
+}
+
+#[allow(clippy::suspicious_arithmetic_impl)]
+impl<E: Engine> Div for &Commitment<E> {
+    type Output = Commitment<E>;
+
+    fn div(self, rhs: Self) -> Self::Output {
+        Commitment {
+            c: self.c - rhs.c,
+            d: self.d - rhs.d,
+        }
+    }
+}
+
+impl<E: Engine> Neg for &Commitment<E> {
+    type Output = Commitment<E>;
+
+    /// Returns the inverse of the commitment, which commits to the inverse values under the
+    /// inverse randomness.
+    fn neg(self) -> Self::Output {
+        Commitment {
+            c: -self.c,
+            d: -self.d,
+        }
+    }
+}
+
+/// Implements the owned and assigning forms of `Mul`, `Div` and `Neg`, and `Product`, in terms of
+/// the operations on references.
+macro_rules! forward_group_ops {
+    ([$($generics:tt)*] $ty:ty, $identity:ident) => {
+        impl<$($generics)*> Mul for $ty {
+            type Output = $ty;
+
+            fn mul(self, rhs: Self) -> Self::Output {
+                &self * &rhs
+            }
+        }
+
+        impl<$($generics)*> Mul<&$ty> for $ty {
+            type Output = $ty;
+
+            fn mul(self, rhs: &Self) -> Self::Output {
+                &self * rhs
+            }
+        }
+
+        impl<$($generics)*> MulAssign for $ty {
+            fn mul_assign(&mut self, rhs: Self) {
+                *self = &*self * &rhs;
+            }
+        }
+
+        impl<$($generics)*> MulAssign<&$ty> for $ty {
+            fn mul_assign(&mut self, rhs: &Self) {
+                *self = &*self * rhs;
+            }
+        }
+
+        impl<$($generics)*> Div for $ty {
+            type Output = $ty;
+
+            fn div(self, rhs: Self) -> Self::Output {
+                &self / &rhs
+            }
+        }
+
+        impl<$($generics)*> Div<&$ty> for $ty {
+            type Output = $ty;
+
+            fn div(self, rhs: &Self) -> Self::Output {
+                &self / rhs
+            }
+        }
+
+        impl<$($generics)*> DivAssign for $ty {
+            fn div_assign(&mut self, rhs: Self) {
+                *self = &*self / &rhs;
+            }
+        }
+
+        impl<$($generics)*> DivAssign<&$ty> for $ty {
+            fn div_assign(&mut self, rhs: &Self) {
+                *self = &*self / rhs;
+            }
+        }
+
+        impl<$($generics)*> Neg for $ty {
+            type Output = $ty;
+
+            fn neg(self) -> Self::Output {
+                -&self
+            }
+        }
+
+        impl<$($generics)*> Product for $ty {
+            fn product<I: Iterator<Item = Self>>(iter: I) -> Self {
+                iter.fold(<$ty>::$identity(), |acc, x| &acc * &x)
+            }
+        }
+
+        impl<'a, $($generics)*> Product<&'a $ty> for $ty {
+            fn product<I: Iterator<Item = &'a Self>>(iter: I) -> Self {
+                iter.fold(<$ty>::$identity(), |acc, x| &acc * x)
+            }
+        }
+    };
+}
+
+forward_group_ops!([const N: usize, E: Engine] Values<N, E>, identity);
+forward_group_ops!([E: Engine] Randomness<E>, zero);
+forward_group_ops!([E: Engine] Commitment<E>, identity);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::CommitmentKey;
+    use bls12_381_plus::Scalar;
+    use ff::Field;
+    use rand::thread_rng;
+
+    #[test]
+    fn linear_combinations_open_correctly() {
+        let ck = CommitmentKey::<2>::generate();
+        let (v1, v2, v3) = (Values::random(), Values::random(), Values::random());
+        let (c1, r1) = ck.commit(&v1);
+        let (c2, r2) = ck.commit(&v2);
+        let (c3, r3) = ck.commit(&v3);
+
+        let a = Scalar::random(thread_rng());
+        let b = Scalar::random(thread_rng());
+        let c = c1.pow(&a) * c2.pow(&b) / c3;
+        let v = v1.pow(&a) * v2.pow(&b) / v3;
+        let r = r1.pow(&a) * r2.pow(&b) / r3;
+        assert_eq!(ck.verify(&c, &v, &r), Ok(()));
+    }
+
+    #[test]
+    fn identities_and_inverses() {
+        let ck = CommitmentKey::<2>::generate();
+        assert_eq!(
+            ck.commit_with_randomness(&Values::identity(), &Randomness::zero()),
+            Commitment::identity()
+        );
+
+        let value = Values::random();
+        let (c, r) = ck.commit(&value);
+        assert_eq!(&c * &-&c, Commitment::identity());
+        assert_eq!(ck.verify(&-c, &-value, &-r), Ok(()));
+    }
+
+    #[test]
+    fn product_matches_repeated_multiplication() {
+        let ck = CommitmentKey::<2>::generate();
+        let openings: Vec<_> = (0..3)
+            .map(|_| {
+                let value = Values::random();
+                let (c, r) = ck.commit(&value);
+                (c, value, r)
+            })
+            .collect();
+
+        let c: Commitment = openings.iter().map(|(c, _, _)| c).product();
+        let mut v = Values::identity();
+        let mut r = Randomness::zero();
+        for (_, value, randomness) in &openings {
+            v *= value;
+            r *= randomness;
+        }
+        assert_eq!(ck.verify(&c, &v, &r), Ok(()));
+        assert_eq!(
+            Vec::<Commitment>::new().into_iter().product::<Commitment>(),
+            Commitment::identity()
+        );
+    }
+}
@@ -0,0 +1,112 @@
+//! Values in prepared form, for committing to the same vector under several keys.
+//!
+//! Preparing a G2 element precomputes the line functions of the Miller loop, which otherwise
+//! happens on every call to [`CommitmentKey::commit_with_randomness`] and
+//! [`CommitmentKey::verify`].
+
+use crate::{Commitment, CommitmentKey, Error, Randomness, Values};
+use bls12_381_plus::Bls12;
+use pairing::MultiMillerLoop;
+use rand::thread_rng;
+use rand_core::CryptoRngCore;
+
+/// A [`Values`] vector with its elements in prepared form.
+///
+/// A prepared element can take up tens of kilobytes, so the elements are kept on the heap.
+pub struct PreparedValues<const N: usize, E: MultiMillerLoop = Bls12> {
+    values: Box<[E::G2Prepared]>,
+}
+
+impl<const N: usize, E: MultiMillerLoop> PreparedValues<N, E> {
+    pub fn new(value: &Values<N, E>) -> Self {
+        PreparedValues {
+            values: value
+                .values
+                .iter()
+                .copied()
+                .map(E::G2Prepared::from)
+                .collect(),
+        }
+    }
+}
+
+impl<const N: usize, E: MultiMillerLoop> From<&Values<N, E>> for PreparedValues<N, E> {
+    fn from(value: &Values<N, E>) -> Self {
+        Self::new(value)
+    }
+}
+
+impl<const N: usize, E: MultiMillerLoop> CommitmentKey<N, E> {
+    /// Commits to prepared values. The result is equal to
+    /// [`CommitmentKey::commit_with_randomness`] on the original values.
+    pub fn commit_prepared_with_randomness(
+        &self,
+        value: &PreparedValues<N, E>,
+        randomness: &Randomness<E>,
+    ) -> Commitment<E> {
+        self.as_key_ref().commit_prepared(&value.values, randomness)
+    }
+
+    pub fn commit_prepared(&self, value: &PreparedValues<N, E>) -> (Commitment<E>, Randomness<E>) {
+        self.commit_prepared_with_rng(value, &mut thread_rng())
+    }
+
+    /// Commits to prepared values, sampling the randomness from the supplied cryptographically
+    /// secure RNG.
+    pub fn commit_prepared_with_rng(
+        &self,
+        value: &PreparedValues<N, E>,
+        rng: &mut impl CryptoRngCore,
+    ) -> (Commitment<E>, Randomness<E>) {
+        let randomness = Randomness::gen(rng);
+        let commitment = self.commit_prepared_with_randomness(value, &randomness);
+        (commitment, randomness)
+    }
+
+    /// Checks that prepared values and `randomness` open `commitment`, as in
+    /// [`CommitmentKey::verify`].
+    pub fn verify_prepared(
+        &self,
+        commitment: &Commitment<E>,
+        value: &PreparedValues<N, E>,
+        randomness: &Randomness<E>,
+    ) -> Result<(), Error> {
+        self.as_key_ref()
+            .verify_prepared(commitment, &value.values, randomness)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn prepared_commitments_match_unprepared() {
+        let value = Values::<3>::random();
+        let prepared = PreparedValues::from(&value);
+
+        for _ in 0..2 {
+            let ck = CommitmentKey::<3>::generate();
+            let (c, r) = ck.commit_prepared(&prepared);
+            assert_eq!(ck.commit_with_randomness(&value, &r), c);
+            assert_eq!(ck.verify(&c, &value, &r), Ok(()));
+            assert_eq!(ck.verify_prepared(&c, &prepared, &r), Ok(()));
+
+            let other = PreparedValues::new(&Values::random());
+            assert_eq!(
+                ck.verify_prepared(&c, &other, &r),
+                Err(Error::InvalidOpening)
+            );
+        }
+    }
+
+    #[test]
+    fn prepares_long_vectors_on_the_heap() {
+        let value = Values::<64>::random();
+        let prepared = PreparedValues::new(&value);
+
+        let ck = CommitmentKey::<64>::generate();
+        let (c, r) = ck.commit_prepared(&prepared);
+        assert_eq!(ck.verify(&c, &value, &r), Ok(()));
+    }
+}
@@ -0,0 +1,282 @@
+//! Proof that commitments under two keys hide the same values.
+//!
+//! For `C1 = com1(v; r1)` and `C2 = com2(v; r2)`, the prover sends the announcements
+//! `A1 = com1(x; t1)` and `A2 = com2(x; t2)` with the same random `x`, receives a challenge `e`
+//! and responds with `z = x * v^e`, `u1 = t1 * r1^e` and `u2 = t2 * r2^e`. The verifier accepts
+//! if `(z, u1)` opens `A1 * C1^e` and `(z, u2)` opens `A2 * C2^e`.
+
+use super::{push_commitment, read_commitment, respond_values, Transcript};
+use crate::{
+    check_header, decode_point, gt, Commitment, CommitmentKey, Error, Randomness, Values,
+    FORMAT_VERSION, G2_COMPRESSED_SIZE,
+};
+use bls12_381_plus::{Bls12, G2Affine};
+use ff::Field;
+use pairing::{Engine, MultiMillerLoop};
+use rand_core::CryptoRngCore;
+
+/// The prover of the interactive protocol, holding the openings and the masks of its
+/// announcements.
+pub struct EqualityProver<'a, const N: usize, E: Engine = Bls12> {
+    value: &'a Values<N, E>,
+    randomness: [&'a Randomness<E>; 2],
+    pub(crate) mask_value: Values<N, E>,
+    mask_randomness: [Randomness<E>; 2],
+}
+
+impl<'a, const N: usize, E: MultiMillerLoop> EqualityProver<'a, N, E> {
+    /// Starts a proof that the commitments to `value` under each of the two keys, with the
+    /// respective randomness, hide the same values. Returns the prover together with the
+    /// announcements to send to the verifier.
+    pub fn new(
+        [ck1, ck2]: [&CommitmentKey<N, E>; 2],
+        value: &'a Values<N, E>,
+        randomness: [&'a Randomness<E>; 2],
+        rng: &mut impl CryptoRngCore,
+    ) -> (Self, [Commitment<E>; 2]) {
+        let mask_value = Values::gen(rng);
+        let mask_randomness = [Randomness::gen(rng), Randomness::gen(rng)];
+        let announcements = [
+            ck1.commit_with_randomness(&mask_value, &mask_randomness[0]),
+            ck2.commit_with_randomness(&mask_value, &mask_randomness[1]),
+        ];
+        let prover = EqualityProver {
+            value,
+            randomness,
+            mask_value,
+            mask_randomness,
+        };
+        (prover, announcements)
+    }
+
+    /// Computes the response to the verifier's challenge. The prover is consumed, since
+    /// answering two challenges for the same announcements reveals the openings.
+    pub fn respond(self, challenge: &E::Fr) -> EqualityResponse<N, E> {
+        let [t1, t2] = &self.mask_randomness;
+        let [r1, r2] = self.randomness;
+        EqualityResponse {
+            value: respond_values(&self.mask_value, self.value, challenge),
+            randomness: [t1 * &r1.pow(challenge), t2 * &r2.pow(challenge)],
+        }
+    }
+}
+
+/// The prover's response to a challenge.
+pub struct EqualityResponse<const N: usize, E: Engine = Bls12> {
+    value: Values<N, E>,
+    randomness: [Randomness<E>; 2],
+}
+
+/// The verifier of the interactive protocol.
+pub struct EqualityVerifier<'a, const N: usize, E: Engine = Bls12> {
+    keys: [&'a CommitmentKey<N, E>; 2],
+    commitments: [&'a Commitment<E>; 2],
+    announcements: [Commitment<E>; 2],
+    challenge: E::Fr,
+}
+
+impl<'a, const N: usize, E: MultiMillerLoop> EqualityVerifier<'a, N, E> {
+    /// Receives the prover's announcements for a proof about `commitments`, made under `keys`
+    /// respectively, and samples the challenge.
+    pub fn new(
+        keys: [&'a CommitmentKey<N, E>; 2],
+        commitments: [&'a Commitment<E>; 2],
+        announcements: [Commitment<E>; 2],
+        rng: &mut impl CryptoRngCore,
+    ) -> Self {
+        EqualityVerifier {
+            keys,
+            commitments,
+            announcements,
+            challenge: E::Fr::random(rng),
+        }
+    }
+
+    /// The challenge to send to the prover.
+    pub fn challenge(&self) -> E::Fr {
+        self.challenge
+    }
+
+    /// Checks the prover's response.
+    pub fn verify(self, response: &EqualityResponse<N, E>) -> Result<(), Error> {
+        check(
+            self.keys,
+            self.commitments,
+            &self.announcements,
+            &self.challenge,
+            response,
+        )
+    }
+}
+
+fn check<const N: usize, E: MultiMillerLoop>(
+    keys: [&CommitmentKey<N, E>; 2],
+    commitments: [&Commitment<E>; 2],
+    announcements: &[Commitment<E>; 2],
+    challenge: &E::Fr,
+    response: &EqualityResponse<N, E>,
+) -> Result<(), Error> {
+    for i in 0..2 {
+        let expected = &announcements[i] * &commitments[i].pow(challenge);
+        keys[i].verify(&expected, &response.value, &response.randomness[i])?;
+    }
+    Ok(())
+}
+
+/// A non-interactive proof that two commitments hide the same values, with the challenge derived
+/// by hashing the keys, the commitments and the announcements.
+pub struct EqualityProof<const N: usize, E: Engine = Bls12> {
+    announcements: [Commitment<E>; 2],
+    response: EqualityResponse<N, E>,
+}
+
+impl<const N: usize> EqualityProof<N, Bls12> {
+    /// The length in bytes of the encoding produced by [`EqualityProof::to_bytes`].
+    pub const ENCODED_SIZE: usize = 1 + 4 * gt::GT_COMPRESSED_SIZE + (N + 4) * G2_COMPRESSED_SIZE;
+
+    /// Proves that `commitments`, made under `keys` respectively, both hide `value`.
+    pub fn prove(
+        keys: [&CommitmentKey<N, Bls12>; 2],
+        commitments: [&Commitment<Bls12>; 2],
+        value: &Values<N, Bls12>,
+        randomness: [&Randomness<Bls12>; 2],
+        rng: &mut impl CryptoRngCore,
+    ) -> Self {
+        let (prover, announcements) = EqualityProver::new(keys, value, randomness, rng);
+        let challenge = challenge(keys, commitments, &announcements);
+        EqualityProof {
+            announcements,
+            response: prover.respond(&challenge),
+        }
+    }
+
+    /// Checks the proof against `commitments`, made under `keys` respectively.
+    pub fn verify(
+        &self,
+        keys: [&CommitmentKey<N, Bls12>; 2],
+        commitments: [&Commitment<Bls12>; 2],
+    ) -> Result<(), Error> {
+        let challenge = challenge(keys, commitments, &self.announcements);
+        check(
+            keys,
+            commitments,
+            &self.announcements,
+            &challenge,
+            &self.response,
+        )
+    }
+
+    /// Decodes a proof produced by [`EqualityProof::to_bytes`], checking that every element is
+    /// in its prime-order subgroup.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, Error> {
+        let body = check_header(bytes, Self::ENCODED_SIZE)?;
+        let (a1, body) = read_commitment(body)?;
+        let (a2, body) = read_commitment(body)?;
+
+        let mut points = body.chunks_exact(G2_COMPRESSED_SIZE).map(decode_point);
+        let mut next = || points.next().unwrap_or(Err(Error::InvalidEncoding));
+        let mut values = [G2Affine::identity(); N];
+        for value in &mut values {
+            *value = next()?;
+        }
+        let mut randomness = || -> Result<_, Error> {
+            Ok(Randomness {
+                r: next()?,
+                s: next()?,
+            })
+        };
+
+        Ok(EqualityProof {
+            announcements: [a1, a2],
+            response: EqualityResponse {
+                value: Values::new(values),
+                randomness: [randomness()?, randomness()?],
+            },
+        })
+    }
+
+    /// Encodes the proof as a version byte followed by both compressed announcements, the
+    /// compressed response values and the elements of both response randomnesses.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(Self::ENCODED_SIZE);
+        bytes.push(FORMAT_VERSION);
+        for announcement in &self.announcements {
+            push_commitment(&mut bytes, announcement);
+        }
+        let response = &self.response;
+        let [u1, u2] = &response.randomness;
+        let points = response.value.values.iter();
+        for point in points.chain([&u1.r, &u1.s, &u2.r, &u2.s]) {
+            bytes.extend_from_slice(&point.to_compressed());
+        }
+        bytes
+    }
+}
+
+fn challenge<const N: usize>(
+    keys: [&CommitmentKey<N, Bls12>; 2],
+    commitments: [&Commitment<Bls12>; 2],
+    announcements: &[Commitment<Bls12>; 2],
+) -> bls12_381_plus::Scalar {
+    let mut transcript = Transcript::new(b"equality");
+    for ck in keys {
+        transcript.append(&ck.to_bytes());
+    }
+    for commitment in commitments.into_iter().chain(announcements) {
+        transcript.append_commitment(commitment);
+    }
+    transcript.challenge()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::thread_rng;
+
+    #[test]
+    fn interactive_proof_is_accepted() {
+        let mut rng = thread_rng();
+        let (ck1, ck2) = (CommitmentKey::<2>::generate(), CommitmentKey::generate());
+        let value = Values::random();
+        let (c1, r1) = ck1.commit(&value);
+        let (c2, r2) = ck2.commit(&value);
+
+        let (prover, announcements) =
+            EqualityProver::new([&ck1, &ck2], &value, [&r1, &r2], &mut rng);
+        let verifier = EqualityVerifier::new([&ck1, &ck2], [&c1, &c2], announcements, &mut rng);
+        let response = prover.respond(&verifier.challenge());
+        assert_eq!(verifier.verify(&response), Ok(()));
+    }
+
+    #[test]
+    fn non_interactive_proof_roundtrips_and_verifies() {
+        let mut rng = thread_rng();
+        let (ck1, ck2) = (CommitmentKey::<2>::generate(), CommitmentKey::generate());
+        let value = Values::random();
+        let (c1, r1) = ck1.commit(&value);
+        let (c2, r2) = ck2.commit(&value);
+
+        let proof = EqualityProof::prove([&ck1, &ck2], [&c1, &c2], &value, [&r1, &r2], &mut rng);
+        let decoded = EqualityProof::<2>::from_bytes(&proof.to_bytes()).unwrap();
+        assert_eq!(decoded.verify([&ck1, &ck2], [&c1, &c2]), Ok(()));
+        assert_eq!(
+            decoded.verify([&ck2, &ck1], [&c2, &c1]),
+            Err(Error::InvalidOpening)
+        );
+    }
+
+    #[test]
+    fn different_values_are_rejected() {
+        let mut rng = thread_rng();
+        let (ck1, ck2) = (CommitmentKey::<2>::generate(), CommitmentKey::generate());
+        let value = Values::random();
+        let (c1, r1) = ck1.commit(&value);
+        let (c2, r2) = ck2.commit(&Values::random());
+
+        let proof = EqualityProof::prove([&ck1, &ck2], [&c1, &c2], &value, [&r1, &r2], &mut rng);
+        assert_eq!(
+            proof.verify([&ck1, &ck2], [&c1, &c2]),
+            Err(Error::InvalidOpening)
+        );
+    }
+}
@@ -0,0 +1,88 @@
+//! Zero-knowledge proofs about commitments.
+//!
+//! The interactive protocols are generic over the pairing engine. Their non-interactive versions
+//! derive the challenge by hashing the statement with the Fiat-Shamir transform, and like the
+//! byte encodings are specific to BLS12-381.
+
+mod equality;
+mod opening;
+mod rerandomization;
+
+pub use equality::{EqualityProof, EqualityProver, EqualityResponse, EqualityVerifier};
+pub use opening::{OpeningProof, OpeningProver, OpeningResponse, OpeningVerifier};
+pub use rerandomization::{
+    RerandomizationProof, RerandomizationProver, RerandomizationResponse, RerandomizationVerifier,
+};
+
+use crate::{gt, Commitment, Error, Values};
+use bls12_381_plus::elliptic_curve::hash2curve::ExpandMsgXmd;
+use bls12_381_plus::{Bls12, Scalar};
+use pairing::Engine;
+
+/// Domain separation tag for hashing transcripts to challenges.
+const DST: &[u8] = b"COMMIT-GROTH09-V01-CS01-with-BLS12381-SHA-256-CHALLENGE";
+
+/// The public inputs of a non-interactive proof, hashed to derive its challenge.
+struct Transcript {
+    bytes: Vec<u8>,
+}
+
+impl Transcript {
+    /// Starts a transcript for the protocol named by `label`.
+    fn new(label: &[u8]) -> Self {
+        let mut transcript = Transcript { bytes: Vec::new() };
+        transcript.append(label);
+        transcript
+    }
+
+    /// Appends a length-prefixed message.
+    fn append(&mut self, message: &[u8]) {
+        self.bytes
+            .extend_from_slice(&(message.len() as u64).to_be_bytes());
+        self.bytes.extend_from_slice(message);
+    }
+
+    fn append_commitment(&mut self, commitment: &Commitment<Bls12>) {
+        self.append(&gt::encode(&commitment.c));
+        self.append(&gt::encode(&commitment.d));
+    }
+
+    fn challenge(&self) -> Scalar {
+        Scalar::hash::<ExpandMsgXmd<sha2::Sha256>>(&self.bytes, DST)
+    }
+}
+
+/// Computes the response `mask * value^challenge`. The intermediate `value^challenge` reveals the
+/// opening, so it is wiped under the `zeroize` feature.
+fn respond_values<const N: usize, E: Engine>(
+    mask: &Values<N, E>,
+    value: &Values<N, E>,
+    challenge: &E::Fr,
+) -> Values<N, E> {
+    #[cfg_attr(not(feature = "zeroize"), allow(unused_mut))]
+    let mut power = value.pow(challenge);
+    let response = mask * &power;
+    #[cfg(feature = "zeroize")]
+    zeroize::Zeroize::zeroize(&mut power);
+    response
+}
+
+/// Appends a commitment in compressed form, without a version byte.
+fn push_commitment(bytes: &mut Vec<u8>, commitment: &Commitment<Bls12>) {
+    bytes.extend_from_slice(&gt::compress(&commitment.c));
+    bytes.extend_from_slice(&gt::compress(&commitment.d));
+}
+
+/// Reads a commitment written by [`push_commitment`], returning it with the remaining bytes.
+fn read_commitment(bytes: &[u8]) -> Result<(Commitment<Bls12>, &[u8]), Error> {
+    if bytes.len() < 2 * gt::GT_COMPRESSED_SIZE {
+        return Err(Error::InvalidEncoding);
+    }
+    let (c, bytes) = bytes.split_at(gt::GT_COMPRESSED_SIZE);
+    let (d, bytes) = bytes.split_at(gt::GT_COMPRESSED_SIZE);
+    let commitment = Commitment {
+        c: gt::decompress(c)?,
+        d: gt::decompress(d)?,
+    };
+    Ok((commitment, bytes))
+}
@@ -0,0 +1,258 @@
+//! Proof of knowledge of an opening of a commitment.
+//!
+//! To prove knowledge of `(v, r)` with `C = com(v; r)`, the prover sends the announcement
+//! `A = com(x; t)` for random `x` and `t`, receives a challenge `e` and responds with
+//! `z = x * v^e` and `u = t * r^e`. By the homomorphism, the verifier accepts if `(z, u)` opens
+//! `A * C^e`.
+
+use super::{push_commitment, read_commitment, respond_values, Transcript};
+use crate::{
+    check_header, decode_point, gt, Commitment, CommitmentKey, Error, Randomness, Values,
+    FORMAT_VERSION, G2_COMPRESSED_SIZE,
+};
+use bls12_381_plus::{Bls12, G2Affine};
+use ff::Field;
+use pairing::{Engine, MultiMillerLoop};
+use rand_core::CryptoRngCore;
+
+/// The prover of the interactive protocol, holding an opening and the masks of its announcement.
+pub struct OpeningProver<'a, const N: usize, E: Engine = Bls12> {
+    value: &'a Values<N, E>,
+    randomness: &'a Randomness<E>,
+    pub(crate) mask_value: Values<N, E>,
+    mask_randomness: Randomness<E>,
+}
+
+impl<'a, const N: usize, E: MultiMillerLoop> OpeningProver<'a, N, E> {
+    /// Starts a proof of knowledge of `(value, randomness)`, returning the prover together with
+    /// the announcement to send to the verifier.
+    pub fn new(
+        ck: &CommitmentKey<N, E>,
+        value: &'a Values<N, E>,
+        randomness: &'a Randomness<E>,
+        rng: &mut impl CryptoRngCore,
+    ) -> (Self, Commitment<E>) {
+        let mask_value = Values::gen(rng);
+        let mask_randomness = Randomness::gen(rng);
+        let announcement = ck.commit_with_randomness(&mask_value, &mask_randomness);
+        let prover = OpeningProver {
+            value,
+            randomness,
+            mask_value,
+            mask_randomness,
+        };
+        (prover, announcement)
+    }
+
+    /// Computes the response to the verifier's challenge. The prover is consumed, since
+    /// answering two challenges for the same announcement reveals the opening.
+    pub fn respond(self, challenge: &E::Fr) -> OpeningResponse<N, E> {
+        OpeningResponse {
+            value: respond_values(&self.mask_value, self.value, challenge),
+            randomness: &self.mask_randomness * &self.randomness.pow(challenge),
+        }
+    }
+}
+
+/// The prover's response to a challenge.
+pub struct OpeningResponse<const N: usize, E: Engine = Bls12> {
+    value: Values<N, E>,
+    randomness: Randomness<E>,
+}
+
+/// The verifier of the interactive protocol.
+pub struct OpeningVerifier<'a, const N: usize, E: Engine = Bls12> {
+    ck: &'a CommitmentKey<N, E>,
+    commitment: &'a Commitment<E>,
+    announcement: Commitment<E>,
+    challenge: E::Fr,
+}
+
+impl<'a, const N: usize, E: MultiMillerLoop> OpeningVerifier<'a, N, E> {
+    /// Receives the prover's announcement for a proof about `commitment`, and samples the
+    /// challenge.
+    pub fn new(
+        ck: &'a CommitmentKey<N, E>,
+        commitment: &'a Commitment<E>,
+        announcement: Commitment<E>,
+        rng: &mut impl CryptoRngCore,
+    ) -> Self {
+        OpeningVerifier {
+            ck,
+            commitment,
+            announcement,
+            challenge: E::Fr::random(rng),
+        }
+    }
+
+    /// The challenge to send to the prover.
+    pub fn challenge(&self) -> E::Fr {
+        self.challenge
+    }
+
+    /// Checks the prover's response.
+    pub fn verify(self, response: &OpeningResponse<N, E>) -> Result<(), Error> {
+        check(
+            self.ck,
+            self.commitment,
+            &self.announcement,
+            &self.challenge,
+            response,
+        )
+    }
+}
+
+fn check<const N: usize, E: MultiMillerLoop>(
+    ck: &CommitmentKey<N, E>,
+    commitment: &Commitment<E>,
+    announcement: &Commitment<E>,
+    challenge: &E::Fr,
+    response: &OpeningResponse<N, E>,
+) -> Result<(), Error> {
+    let expected = announcement * &commitment.pow(challenge);
+    ck.verify(&expected, &response.value, &response.randomness)
+}
+
+/// A non-interactive proof of knowledge of an opening, with the challenge derived by hashing the
+/// key, the commitment and the announcement.
+pub struct OpeningProof<const N: usize, E: Engine = Bls12> {
+    announcement: Commitment<E>,
+    response: OpeningResponse<N, E>,
+}
+
+impl<const N: usize> OpeningProof<N, Bls12> {
+    /// The length in bytes of the encoding produced by [`OpeningProof::to_bytes`].
+    pub const ENCODED_SIZE: usize = 1 + 2 * gt::GT_COMPRESSED_SIZE + (N + 2) * G2_COMPRESSED_SIZE;
+
+    /// Proves knowledge of `(value, randomness)` opening `commitment`.
+    pub fn prove(
+        ck: &CommitmentKey<N, Bls12>,
+        commitment: &Commitment<Bls12>,
+        value: &Values<N, Bls12>,
+        randomness: &Randomness<Bls12>,
+        rng: &mut impl CryptoRngCore,
+    ) -> Self {
+        let (prover, announcement) = OpeningProver::new(ck, value, randomness, rng);
+        let challenge = challenge(ck, commitment, &announcement);
+        OpeningProof {
+            announcement,
+            response: prover.respond(&challenge),
+        }
+    }
+
+    /// Checks the proof against `commitment`.
+    pub fn verify(
+        &self,
+        ck: &CommitmentKey<N, Bls12>,
+        commitment: &Commitment<Bls12>,
+    ) -> Result<(), Error> {
+        let challenge = challenge(ck, commitment, &self.announcement);
+        check(
+            ck,
+            commitment,
+            &self.announcement,
+            &challenge,
+            &self.response,
+        )
+    }
+
+    /// Decodes a proof produced by [`OpeningProof::to_bytes`], checking that every element is in
+    /// its prime-order subgroup.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, Error> {
+        let body = check_header(bytes, Self::ENCODED_SIZE)?;
+        let (announcement, body) = read_commitment(body)?;
+
+        let mut points = body.chunks_exact(G2_COMPRESSED_SIZE).map(decode_point);
+        let mut next = || points.next().unwrap_or(Err(Error::InvalidEncoding));
+        let mut values = [G2Affine::identity(); N];
+        for value in &mut values {
+            *value = next()?;
+        }
+        let randomness = Randomness {
+            r: next()?,
+            s: next()?,
+        };
+
+        Ok(OpeningProof {
+            announcement,
+            response: OpeningResponse {
+                value: Values::new(values),
+                randomness,
+            },
+        })
+    }
+
+    /// Encodes the proof as a version byte followed by the compressed announcement, the
+    /// compressed response values and the two elements of the response randomness.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(Self::ENCODED_SIZE);
+        bytes.push(FORMAT_VERSION);
+        push_commitment(&mut bytes, &self.announcement);
+        let response = &self.response;
+        let points = response.value.values.iter();
+        for point in points.chain([&response.randomness.r, &response.randomness.s]) {
+            bytes.extend_from_slice(&point.to_compressed());
+        }
+        bytes
+    }
+}
+
+fn challenge<const N: usize>(
+    ck: &CommitmentKey<N, Bls12>,
+    commitment: &Commitment<Bls12>,
+    announcement: &Commitment<Bls12>,
+) -> bls12_381_plus::Scalar {
+    let mut transcript = Transcript::new(b"opening");
+    transcript.append(&ck.to_bytes());
+    transcript.append_commitment(commitment);
+    transcript.append_commitment(announcement);
+    transcript.challenge()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::thread_rng;
+
+    #[test]
+    fn interactive_proof_is_accepted() {
+        let mut rng = thread_rng();
+        let ck = CommitmentKey::<3>::generate();
+        let value = Values::random();
+        let (c, r) = ck.commit(&value);
+
+        let (prover, announcement) = OpeningProver::new(&ck, &value, &r, &mut rng);
+        let verifier = OpeningVerifier::new(&ck, &c, announcement, &mut rng);
+        let response = prover.respond(&verifier.challenge());
+        assert_eq!(verifier.verify(&response), Ok(()));
+    }
+
+    #[test]
+    fn non_interactive_proof_roundtrips_and_verifies() {
+        let mut rng = thread_rng();
+        let ck = CommitmentKey::<2>::generate();
+        let value = Values::random();
+        let (c, r) = ck.commit(&value);
+
+        let proof = OpeningProof::prove(&ck, &c, &value, &r, &mut rng);
+        let bytes = proof.to_bytes();
+        assert_eq!(bytes.len(), OpeningProof::<2>::ENCODED_SIZE);
+        let decoded = OpeningProof::<2>::from_bytes(&bytes).unwrap();
+        assert_eq!(decoded.verify(&ck, &c), Ok(()));
+
+        let (other, _) = ck.commit(&Values::random());
+        assert_eq!(proof.verify(&ck, &other), Err(Error::InvalidOpening));
+    }
+
+    #[test]
+    fn proof_without_opening_is_rejected() {
+        let mut rng = thread_rng();
+        let ck = CommitmentKey::<2>::generate();
+        let (c, _) = ck.commit(&Values::random());
+
+        // A prover that does not know the opening uses an unrelated one.
+        let (value, r) = (Values::random(), Randomness::gen(&mut rng));
+        let proof = OpeningProof::prove(&ck, &c, &value, &r, &mut rng);
+        assert_eq!(proof.verify(&ck, &c), Err(Error::InvalidOpening));
+    }
+}
@@ -0,0 +1,242 @@
+//! Proof that a commitment was rerandomized correctly.
+//!
+//! If `C' = C * com(1; delta)`, the quotient `D = C' / C` is a commitment to the identity vector,
+//! so by the binding property `C'` hides the same values as `C`. The prover shows knowledge of
+//! `delta` by sending the announcement `A = com(1; t)` for a random `t`, receiving a challenge
+//! `e` and responding with `u = t * delta^e`. The verifier accepts if `u` opens `A * D^e` to the
+//! identity vector. Nothing about the committed values is involved.
+
+use super::{push_commitment, read_commitment, Transcript};
+use crate::{
+    check_header, decode_point, gt, Commitment, CommitmentKey, Error, Randomness, FORMAT_VERSION,
+    G2_COMPRESSED_SIZE,
+};
+use bls12_381_plus::Bls12;
+use ff::Field;
+use pairing::{Engine, MultiMillerLoop};
+use rand_core::CryptoRngCore;
+
+/// The prover of the interactive protocol, holding the rerandomization randomness and the mask of
+/// its announcement.
+pub struct RerandomizationProver<'a, E: Engine = Bls12> {
+    delta: &'a Randomness<E>,
+    mask: Randomness<E>,
+}
+
+impl<'a, E: MultiMillerLoop> RerandomizationProver<'a, E> {
+    /// Starts a proof for a commitment rerandomized with `delta` by
+    /// [`Commitment::rerandomize`], returning the prover together with the announcement to send
+    /// to the verifier.
+    pub fn new<const N: usize>(
+        ck: &CommitmentKey<N, E>,
+        delta: &'a Randomness<E>,
+        rng: &mut impl CryptoRngCore,
+    ) -> (Self, Commitment<E>) {
+        let mask = Randomness::gen(rng);
+        let announcement = ck.as_key_ref().commit_with_randomness(&[], &mask);
+        (RerandomizationProver { delta, mask }, announcement)
+    }
+
+    /// Computes the response to the verifier's challenge. The prover is consumed, since
+    /// answering two challenges for the same announcement reveals `delta`.
+    pub fn respond(self, challenge: &E::Fr) -> RerandomizationResponse<E> {
+        RerandomizationResponse {
+            randomness: &self.mask * &self.delta.pow(challenge),
+        }
+    }
+}
+
+/// The prover's response to a challenge.
+pub struct RerandomizationResponse<E: Engine = Bls12> {
+    randomness: Randomness<E>,
+}
+
+/// The verifier of the interactive protocol.
+pub struct RerandomizationVerifier<'a, const N: usize, E: Engine = Bls12> {
+    ck: &'a CommitmentKey<N, E>,
+    old: &'a Commitment<E>,
+    new: &'a Commitment<E>,
+    announcement: Commitment<E>,
+    challenge: E::Fr,
+}
+
+impl<'a, const N: usize, E: MultiMillerLoop> RerandomizationVerifier<'a, N, E> {
+    /// Receives the prover's announcement for a proof that `new` is a rerandomization of `old`,
+    /// and samples the challenge.
+    pub fn new(
+        ck: &'a CommitmentKey<N, E>,
+        old: &'a Commitment<E>,
+        new: &'a Commitment<E>,
+        announcement: Commitment<E>,
+        rng: &mut impl CryptoRngCore,
+    ) -> Self {
+        RerandomizationVerifier {
+            ck,
+            old,
+            new,
+            announcement,
+            challenge: E::Fr::random(rng),
+        }
+    }
+
+    /// The challenge to send to the prover.
+    pub fn challenge(&self) -> E::Fr {
+        self.challenge
+    }
+
+    /// Checks the prover's response.
+    pub fn verify(self, response: &RerandomizationResponse<E>) -> Result<(), Error> {
+        check(
+            self.ck,
+            [self.old, self.new],
+            &self.announcement,
+            &self.challenge,
+            response,
+        )
+    }
+}
+
+fn check<const N: usize, E: MultiMillerLoop>(
+    ck: &CommitmentKey<N, E>,
+    [old, new]: [&Commitment<E>; 2],
+    announcement: &Commitment<E>,
+    challenge: &E::Fr,
+    response: &RerandomizationResponse<E>,
+) -> Result<(), Error> {
+    let expected = announcement * &(new / old).pow(challenge);
+    ck.verify_slice(&expected, &[], &response.randomness)
+}
+
+/// A non-interactive proof that a commitment is a rerandomization of another, with the challenge
+/// derived by hashing the key, both commitments and the announcement.
+pub struct RerandomizationProof<E: Engine = Bls12> {
+    announcement: Commitment<E>,
+    response: RerandomizationResponse<E>,
+}
+
+impl RerandomizationProof<Bls12> {
+    /// The length in bytes of the encoding produced by [`RerandomizationProof::to_bytes`].
+    pub const ENCODED_SIZE: usize = 1 + 2 * gt::GT_COMPRESSED_SIZE + 2 * G2_COMPRESSED_SIZE;
+
+    /// Proves that `new` was obtained from `old` by [`Commitment::rerandomize`] with `delta`.
+    pub fn prove<const N: usize>(
+        ck: &CommitmentKey<N, Bls12>,
+        old: &Commitment<Bls12>,
+        new: &Commitment<Bls12>,
+        delta: &Randomness<Bls12>,
+        rng: &mut impl CryptoRngCore,
+    ) -> Self {
+        let (prover, announcement) = RerandomizationProver::new(ck, delta, rng);
+        let challenge = challenge(ck, [old, new], &announcement);
+        RerandomizationProof {
+            announcement,
+            response: prover.respond(&challenge),
+        }
+    }
+
+    /// Checks the proof that `new` is a rerandomization of `old`.
+    pub fn verify<const N: usize>(
+        &self,
+        ck: &CommitmentKey<N, Bls12>,
+        old: &Commitment<Bls12>,
+        new: &Commitment<Bls12>,
+    ) -> Result<(), Error> {
+        let challenge = challenge(ck, [old, new], &self.announcement);
+        check(
+            ck,
+            [old, new],
+            &self.announcement,
+            &challenge,
+            &self.response,
+        )
+    }
+
+    /// Decodes a proof produced by [`RerandomizationProof::to_bytes`], checking that every
+    /// element is in its prime-order subgroup.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, Error> {
+        let body = check_header(bytes, Self::ENCODED_SIZE)?;
+        let (announcement, body) = read_commitment(body)?;
+        let (r, s) = body.split_at(G2_COMPRESSED_SIZE);
+        let randomness = Randomness {
+            r: decode_point(r)?,
+            s: decode_point(s)?,
+        };
+        Ok(RerandomizationProof {
+            announcement,
+            response: RerandomizationResponse { randomness },
+        })
+    }
+
+    /// Encodes the proof as a version byte followed by the compressed announcement and the two
+    /// elements of the response randomness.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(Self::ENCODED_SIZE);
+        bytes.push(FORMAT_VERSION);
+        push_commitment(&mut bytes, &self.announcement);
+        let randomness = &self.response.randomness;
+        bytes.extend_from_slice(&randomness.r.to_compressed());
+        bytes.extend_from_slice(&randomness.s.to_compressed());
+        bytes
+    }
+}
+
+fn challenge<const N: usize>(
+    ck: &CommitmentKey<N, Bls12>,
+    commitments: [&Commitment<Bls12>; 2],
+    announcement: &Commitment<Bls12>,
+) -> bls12_381_plus::Scalar {
+    let mut transcript = Transcript::new(b"rerandomization");
+    transcript.append(&ck.to_bytes());
+    for commitment in commitments.into_iter().chain([announcement]) {
+        transcript.append_commitment(commitment);
+    }
+    transcript.challenge()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Values;
+    use rand::thread_rng;
+
+    #[test]
+    fn rerandomized_commitment_opens_to_same_values() {
+        let ck = CommitmentKey::<3>::generate();
+        let value = Values::random();
+        let (c, r) = ck.commit(&value);
+
+        let (new, delta) = c.rerandomize(&ck, &mut thread_rng());
+        assert_ne!(new, c);
+        assert_eq!(ck.verify(&new, &value, &(&r * &delta)), Ok(()));
+    }
+
+    #[test]
+    fn interactive_proof_is_accepted() {
+        let mut rng = thread_rng();
+        let ck = CommitmentKey::<2>::generate();
+        let (c, _) = ck.commit(&Values::random());
+        let (new, delta) = c.rerandomize(&ck, &mut rng);
+
+        let (prover, announcement) = RerandomizationProver::new(&ck, &delta, &mut rng);
+        let verifier = RerandomizationVerifier::new(&ck, &c, &new, announcement, &mut rng);
+        let response = prover.respond(&verifier.challenge());
+        assert_eq!(verifier.verify(&response), Ok(()));
+    }
+
+    #[test]
+    fn non_interactive_proof_roundtrips_and_verifies() {
+        let mut rng = thread_rng();
+        let ck = CommitmentKey::<2>::generate();
+        let (c, _) = ck.commit(&Values::random());
+        let (new, delta) = c.rerandomize(&ck, &mut rng);
+
+        let proof = RerandomizationProof::prove(&ck, &c, &new, &delta, &mut rng);
+        let decoded = RerandomizationProof::from_bytes(&proof.to_bytes()).unwrap();
+        assert_eq!(decoded.verify(&ck, &c, &new), Ok(()));
+
+        // A fresh commitment to other values is not a rerandomization of `c`.
+        let (other, _) = ck.commit(&Values::random());
+        let proof = RerandomizationProof::prove(&ck, &c, &other, &delta, &mut rng);
+        assert_eq!(proof.verify(&ck, &c, &other), Err(Error::InvalidOpening));
+    }
+}
@@ -0,0 +1,133 @@
+//! Wiping of secrets from memory, behind the `zeroize` feature.
+//!
+//! [`Randomness`], [`RandomnessG1`], [`Trapdoor`] and the interactive provers are wiped when
+//! dropped. [`Values`] can be wiped explicitly, since they are only secret as part of an
+//! opening. The provers also wipe the intermediate `value^challenge` of their responses, and the
+//! `serde` impls wipe their copies of the byte encodings.
+//!
+//! Copies outside the crate's control are not wiped. These include the encodings returned by
+//! `to_bytes`, buffers inside the serializer or deserializer, and values that were moved, since
+//! a move may leave a copy behind on the stack.
+//!
+//! The engine's group and field types are not required to implement [`Zeroize`], so each element
+//! is overwritten with the identity or zero with a volatile write, as `zeroize` does for
+//! [`DefaultIsZeroes`](zeroize::DefaultIsZeroes) types.
+
+use crate::proofs::{EqualityProver, OpeningProver, RerandomizationProver};
+use crate::{Randomness, RandomnessG1, Trapdoor, Values};
+use ff::Field;
+use group::prime::PrimeCurveAffine;
+use pairing::Engine;
+use std::sync::atomic::{compiler_fence, Ordering};
+use zeroize::{Zeroize, ZeroizeOnDrop};
+
+/// Overwrites `place` with `value` in a way the compiler cannot elide.
+fn wipe<T: Copy>(place: &mut T, value: T) {
+    // SAFETY: `place` is a valid, aligned and exclusive reference, and `T: Copy` has no drop glue
+    // that could observe the overwritten value.
+    unsafe { std::ptr::write_volatile(place, value) };
+    compiler_fence(Ordering::SeqCst);
+}
+
+impl<const N: usize, E: Engine> Zeroize for Values<N, E> {
+    fn zeroize(&mut self) {
+        for value in &mut self.values {
+            wipe(value, E::G2Affine::identity());
+        }
+    }
+}
+
+impl<E: Engine> Zeroize for Randomness<E> {
+    fn zeroize(&mut self) {
+        wipe(&mut self.r, E::G2Affine::identity());
+        wipe(&mut self.s, E::G2Affine::identity());
+    }
+}
+
+impl<E: Engine> Drop for Randomness<E> {
+    fn drop(&mut self) {
+        self.zeroize();
+    }
+}
+
+impl<E: Engine> ZeroizeOnDrop for Randomness<E> {}
+
+impl<E: Engine> Zeroize for RandomnessG1<E> {
+    fn zeroize(&mut self) {
+        wipe(&mut self.r, E::G1Affine::identity());
+        wipe(&mut self.s, E::G1Affine::identity());
+    }
+}
+
+impl<E: Engine> Drop for RandomnessG1<E> {
+    fn drop(&mut self) {
+        self.zeroize();
+    }
+}
+
+impl<E: Engine> ZeroizeOnDrop for RandomnessG1<E> {}
+
+impl<const N: usize, E: Engine> Zeroize for Trapdoor<N, E> {
+    fn zeroize(&mut self) {
+        for x in self.gamma.iter_mut().chain(&mut self.delta) {
+            wipe(x, E::Fr::ZERO);
+        }
+    }
+}
+
+impl<const N: usize, E: Engine> Drop for Trapdoor<N, E> {
+    fn drop(&mut self) {
+        self.zeroize();
+    }
+}
+
+impl<const N: usize, E: Engine> ZeroizeOnDrop for Trapdoor<N, E> {}
+
+// The provers hold the masks of their announcements, which reveal the opening together with the
+// response. The mask randomness is wiped on its own drop.
+
+impl<const N: usize, E: Engine> Drop for OpeningProver<'_, N, E> {
+    fn drop(&mut self) {
+        self.mask_value.zeroize();
+    }
+}
+
+impl<const N: usize, E: Engine> ZeroizeOnDrop for OpeningProver<'_, N, E> {}
+
+impl<const N: usize, E: Engine> Drop for EqualityProver<'_, N, E> {
+    fn drop(&mut self) {
+        self.mask_value.zeroize();
+    }
+}
+
+impl<const N: usize, E: Engine> ZeroizeOnDrop for EqualityProver<'_, N, E> {}
+
+impl<E: Engine> ZeroizeOnDrop for RerandomizationProver<'_, E> {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::CommitmentKey;
+    use rand::thread_rng;
+
+    #[test]
+    fn zeroize_resets_to_identity() {
+        let ck = CommitmentKey::<2>::generate();
+        let mut value = Values::random();
+        let mut r = Randomness::gen(&mut thread_rng());
+        value.zeroize();
+        r.zeroize();
+        assert_eq!(
+            ck.commit_with_randomness(&value, &r),
+            ck.commit_with_randomness(&Values::identity(), &Randomness::zero())
+        );
+
+        let (_, mut td) = CommitmentKey::<2>::generate_with_trapdoor();
+        td.zeroize();
+        assert!(td
+            .gamma
+            .iter()
+            .chain(&td.delta)
+            .all(|x| bool::from(x.is_zero())));
+    }
+}
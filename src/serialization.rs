@@ -0,0 +1,190 @@
+//! `serde` support, built on the byte encodings of each type.
+//!
+//! Human-readable formats get the encoding as a lowercase hex string, and binary formats get it
+//! as a byte array. Deserialization goes through `from_bytes`, so every point is validated.
+
+use crate::{Commitment, CommitmentKey, Randomness, Values};
+use serde::de::{self, Deserialize, Deserializer, SeqAccess, Visitor};
+use serde::ser::{Serialize, Serializer};
+use std::fmt;
+
+/// A buffer holding an encoding, which is wiped on drop under the `zeroize` feature since the
+/// encoding of [`Randomness`] is secret.
+#[cfg(feature = "zeroize")]
+type Buffer<T> = zeroize::Zeroizing<T>;
+#[cfg(not(feature = "zeroize"))]
+type Buffer<T> = T;
+
+#[cfg(feature = "zeroize")]
+fn buffer<T: zeroize::Zeroize>(value: T) -> Buffer<T> {
+    zeroize::Zeroizing::new(value)
+}
+
+#[cfg(not(feature = "zeroize"))]
+fn buffer<T>(value: T) -> Buffer<T> {
+    value
+}
+
+fn serialize_bytes<S: Serializer>(bytes: &[u8], serializer: S) -> Result<S::Ok, S::Error> {
+    if serializer.is_human_readable() {
+        serializer.serialize_str(&buffer(hex::encode(bytes)))
+    } else {
+        serializer.serialize_bytes(bytes)
+    }
+}
+
+fn deserialize_bytes<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Buffer<Vec<u8>>, D::Error> {
+    let bytes = if deserializer.is_human_readable() {
+        deserializer.deserialize_str(BytesVisitor)
+    } else {
+        deserializer.deserialize_bytes(BytesVisitor)
+    };
+    bytes.map(buffer)
+}
+
+/// Accepts a hex string, or bytes given directly or as a sequence.
+struct BytesVisitor;
+
+impl<'de> Visitor<'de> for BytesVisitor {
+    type Value = Vec<u8>;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("a hex string or a byte array")
+    }
+
+    fn visit_str<E: de::Error>(self, v: &str) -> Result<Self::Value, E> {
+        hex::decode(v).map_err(E::custom)
+    }
+
+    fn visit_bytes<E: de::Error>(self, v: &[u8]) -> Result<Self::Value, E> {
+        Ok(v.to_vec())
+    }
+
+    fn visit_byte_buf<E: de::Error>(self, v: Vec<u8>) -> Result<Self::Value, E> {
+        Ok(v)
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+        let mut bytes = Vec::new();
+        while let Some(byte) = seq.next_element()? {
+            bytes.push(byte);
+        }
+        Ok(bytes)
+    }
+}
+
+/// Implements `Serialize` and `Deserialize` in terms of a pair of encoding methods.
+macro_rules! impl_serde {
+    ([$($generics:tt)*] $ty:ty, $to_bytes:ident, $from_bytes:ident) => {
+        impl<$($generics)*> Serialize for $ty {
+            fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                serialize_bytes(&buffer(self.$to_bytes()), serializer)
+            }
+        }
+
+        impl<'de, $($generics)*> Deserialize<'de> for $ty {
+            fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                let bytes = deserialize_bytes(deserializer)?;
+                <$ty>::$from_bytes(&bytes).map_err(de::Error::custom)
+            }
+        }
+    };
+}
+
+impl_serde!([const N: usize] Values<N>, to_bytes, from_bytes);
+impl_serde!([] Randomness, to_bytes, from_bytes);
+impl_serde!([] Commitment, to_compressed, from_compressed);
+impl_serde!([const N: usize] CommitmentKey<N>, to_bytes, from_bytes);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Error;
+    use serde::de::value::{BytesDeserializer, Error as ValueError, StrDeserializer};
+    use serde::de::DeserializeOwned;
+
+    fn from_hex<'de, T: Deserialize<'de>>(hex: &'de str) -> Result<T, ValueError> {
+        T::deserialize(StrDeserializer::<ValueError>::new(hex))
+    }
+
+    fn from_raw<'de, T: Deserialize<'de>>(bytes: &'de [u8]) -> Result<T, ValueError> {
+        T::deserialize(BytesDeserializer::<ValueError>::new(bytes))
+    }
+
+    /// Round-trips `value` through JSON and bincode, checking that JSON holds `encoding` as a hex
+    /// string and bincode holds it as raw bytes.
+    fn roundtrip<T: Serialize + DeserializeOwned>(value: &T, encoding: &[u8]) -> [T; 2] {
+        let json = serde_json::to_string(value).unwrap();
+        assert_eq!(json, format!("\"{}\"", hex::encode(encoding)));
+
+        let binary = bincode::serialize(value).unwrap();
+        let mut expected = (encoding.len() as u64).to_le_bytes().to_vec();
+        expected.extend_from_slice(encoding);
+        assert_eq!(binary, expected);
+
+        [
+            serde_json::from_str(&json).unwrap(),
+            bincode::deserialize(&binary).unwrap(),
+        ]
+    }
+
+    #[test]
+    fn roundtrips_through_json_and_bincode() {
+        let ck = CommitmentKey::<2>::generate();
+        let value = Values::random();
+        let (c, r) = ck.commit(&value);
+
+        for decoded in roundtrip(&ck, &ck.to_bytes()) {
+            assert_eq!(decoded.to_bytes(), ck.to_bytes());
+        }
+        for decoded in roundtrip(&value, &value.to_bytes()) {
+            assert_eq!(decoded.to_bytes(), value.to_bytes());
+        }
+        for decoded in roundtrip(&r, &r.to_bytes()) {
+            assert_eq!(decoded.to_bytes(), r.to_bytes());
+        }
+        for decoded in roundtrip(&c, &c.to_compressed()) {
+            assert_eq!(decoded, c);
+        }
+    }
+
+    #[test]
+    fn deserializes_hex_and_bytes() {
+        let ck = CommitmentKey::<2>::generate();
+        let value = Values::random();
+        let (c, r) = ck.commit(&value);
+
+        let ck_bytes = ck.to_bytes();
+        let ck: CommitmentKey<2> = from_hex(&hex::encode(&ck_bytes)).unwrap();
+        assert_eq!(ck.to_bytes(), ck_bytes);
+
+        let value_hex = hex::encode(value.to_bytes());
+        let value: Values<2> = from_hex(&value_hex).unwrap();
+        let r_bytes = r.to_bytes();
+        let r: Randomness = from_raw(&r_bytes).unwrap();
+        let c_bytes = c.to_compressed();
+        let c: Commitment = from_raw(&c_bytes).unwrap();
+        assert_eq!(ck.verify(&c, &value, &r), Ok(()));
+    }
+
+    #[test]
+    fn deserialization_validates_points() {
+        let mut bytes = Values::<1>::random().to_bytes();
+        bytes[1] ^= 0x1f;
+        let expected = Values::<1>::from_bytes(&bytes).err().unwrap();
+        let err = from_raw::<Values<1>>(&bytes).err().unwrap();
+        assert_eq!(err.to_string(), expected.to_string());
+
+        let err = from_hex::<Values<1>>("zz").err().unwrap();
+        assert_eq!(err.to_string(), hex::decode("zz").unwrap_err().to_string());
+
+        let short = from_raw::<Randomness>(&[1, 2, 3]).err().unwrap();
+        let expected = Error::InvalidLength {
+            expected: Randomness::ENCODED_SIZE,
+            actual: 3,
+        };
+        assert_eq!(short.to_string(), expected.to_string());
+    }
+}
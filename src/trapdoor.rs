@@ -0,0 +1,125 @@
+use crate::{gen_g1_elem, CommitmentKey, Randomness, Values};
+use bls12_381_plus::Bls12;
+use ff::Field;
+use group::prime::PrimeCurveAffine;
+use group::Curve;
+use pairing::Engine;
+use rand::thread_rng;
+use rand_core::CryptoRngCore;
+use std::fmt;
+use std::iter::zip;
+
+/// The trapdoor of a commitment key, which allows opening a commitment to any values.
+///
+/// The key elements are generated as `g_i = gr^gamma_i * gs^delta_i` and
+/// `h_i = hr^gamma_i * hs^delta_i`, so that `e(g_i, m) = e(gr, m^gamma_i) * e(gs, m^delta_i)`
+/// and likewise for `h_i`. A change in the committed values can then be absorbed into the
+/// randomness.
+///
+/// Like [`Randomness`], the trapdoor cannot be cloned and its `Debug` output is redacted.
+pub struct Trapdoor<const N: usize, E: Engine = Bls12> {
+    pub(crate) gamma: [E::Fr; N],
+    pub(crate) delta: [E::Fr; N],
+}
+
+impl<const N: usize, E: Engine> fmt::Debug for Trapdoor<N, E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Trapdoor").finish_non_exhaustive()
+    }
+}
+
+impl<const N: usize, E: Engine> CommitmentKey<N, E> {
+    /// Generates a commitment key together with its trapdoor.
+    ///
+    /// The key has the same distribution as one produced by [`CommitmentKey::generate`].
+    pub fn generate_with_trapdoor() -> (CommitmentKey<N, E>, Trapdoor<N, E>) {
+        Self::generate_with_trapdoor_with_rng(&mut thread_rng())
+    }
+
+    /// Generates a commitment key together with its trapdoor, using the supplied
+    /// cryptographically secure RNG.
+    pub fn generate_with_trapdoor_with_rng(
+        rng: &mut impl CryptoRngCore,
+    ) -> (CommitmentKey<N, E>, Trapdoor<N, E>) {
+        let g = E::G1Affine::generator();
+
+        let gr = gen_g1_elem::<E>(rng, g);
+        let hr = gen_g1_elem::<E>(rng, g);
+
+        let gs = gen_g1_elem::<E>(rng, g);
+        let hs = gen_g1_elem::<E>(rng, g);
+
+        let gamma = [(); N].map(|_| E::Fr::random(&mut *rng));
+        let delta = [(); N].map(|_| E::Fr::random(&mut *rng));
+
+        let g_proj: Vec<E::G1> = zip(&gamma, &delta).map(|(x, y)| gr * x + gs * y).collect();
+        let h_proj: Vec<E::G1> = zip(&gamma, &delta).map(|(x, y)| hr * x + hs * y).collect();
+        let mut g_arr = [E::G1Affine::identity(); N];
+        let mut h_arr = [E::G1Affine::identity(); N];
+        E::G1::batch_normalize(&g_proj, &mut g_arr);
+        E::G1::batch_normalize(&h_proj, &mut h_arr);
+
+        let ck = CommitmentKey {
+            g_arr,
+            h_arr,
+            gr,
+            hr,
+            gs,
+            hs,
+        };
+        (ck, Trapdoor { gamma, delta })
+    }
+}
+
+impl<const N: usize, E: Engine> Trapdoor<N, E> {
+    /// Given the opening `(value, randomness)` of a commitment, computes randomness that opens the
+    /// same commitment to `new_value`.
+    pub fn equivocate(
+        &self,
+        value: &Values<N, E>,
+        randomness: &Randomness<E>,
+        new_value: &Values<N, E>,
+    ) -> Randomness<E> {
+        let mut r = randomness.r.to_curve();
+        let mut s = randomness.s.to_curve();
+        for i in 0..N {
+            let diff = value.values[i].to_curve() - new_value.values[i];
+            r += diff * self.gamma[i];
+            s += diff * self.delta[i];
+        }
+        Randomness {
+            r: r.to_affine(),
+            s: s.to_affine(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    #[test]
+    fn equivocate_opens_to_new_values() {
+        let (ck, td) = CommitmentKey::<4>::generate_with_trapdoor();
+        let value = Values::random();
+        let (c, r) = ck.commit(&value);
+
+        let new_value = Values::random();
+        let new_r = td.equivocate(&value, &r, &new_value);
+        assert_eq!(ck.verify(&c, &new_value, &new_r), Ok(()));
+        assert_eq!(ck.commit_with_randomness(&new_value, &new_r), c);
+    }
+
+    #[test]
+    fn generate_with_trapdoor_is_deterministic_under_seeded_rng() {
+        let mut rng = StdRng::seed_from_u64(42);
+        let (ck1, td1) = CommitmentKey::<3>::generate_with_trapdoor_with_rng(&mut rng);
+        let mut rng = StdRng::seed_from_u64(42);
+        let (ck2, td2) = CommitmentKey::<3>::generate_with_trapdoor_with_rng(&mut rng);
+        assert_eq!(ck1.to_bytes(), ck2.to_bytes());
+        assert_eq!(td1.gamma, td2.gamma);
+        assert_eq!(td1.delta, td2.delta);
+    }
+}